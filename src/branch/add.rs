@@ -3,25 +3,34 @@ use anyhow::{anyhow, Result};
 
 use crate::AddCommand;
 use crate::branch::Branch;
-use crate::config::{PROJECT_FORMAT, Editor};
-use crate::db::{project::Project, alias::Alias};
+use crate::config::Editor;
+use crate::format::project_format;
+use crate::db::{project::Project, alias::Alias, template::Template};
 
 impl AddCommand {
     pub fn new(
         project: Option<String>, 
         alias: Option<String>, 
-        proj_name: Option<String>) 
+        proj_name: Option<String>,
+        template: Option<String>) 
     -> Self {
         AddCommand { 
             project, 
             alias, 
-            proj_name}
+            proj_name,
+            template}
     }
 
-    fn add_proj(name: String) -> Result<()> {
+    fn add_proj(name: String, template: Option<&str>) -> Result<()> {
         println!("Adding project {}", name);
 
-        let toml = Editor::input_editor(PROJECT_FORMAT)?;
+        // Placeholders are asked for first so the editor opens on a filled in template
+        let format = match template {
+            Some(template) => Template::get(template)?.fill(&name)?,
+            None => project_format()?,
+        };
+
+        let toml = Editor::input_config(&name, &format)?;
 
         let proj_id = Project::add(name, toml)?;
 
@@ -86,13 +95,13 @@ impl AddCommand {
     fn add_alias(alias: String) -> Result<()> {
         println!("Adding alias {}", alias);
 
-        let proj_name = Input::new()
+        let proj_name: String = Input::new()
             .with_prompt("Enter the name or an alias of a project: ")
             .interact()?;
 
-        let proj_id = Project::get_id(proj_name)?;
+        let project = Project::find(&proj_name)?;
 
-        Alias::add(proj_id, alias)
+        Alias::add(project.id, alias)
     }
 }
 
@@ -100,17 +109,17 @@ impl Branch for AddCommand {
     fn execute(&self) -> Result<()> {
         if let Some(project) = &self.project {
             // Explicit project name provided via --project
-            Self::add_proj(project.to_string())
+            Self::add_proj(project.to_string(), self.template.as_deref())
         } 
         else if let Some(proj_name) = &self.proj_name {
             // Implicit project name or alias provided as a positional argument
-            Self::add_proj(proj_name.to_string())
+            Self::add_proj(proj_name.to_string(), self.template.as_deref())
         } else if let Some(alias) = &self.alias {
             // Explicit alias provided via --alias
             Self::add_alias(alias.to_string())
         } else {
             // No valid input provided
-            return Err(anyhow!("No project name or alias provided."));
+            Err(anyhow!("No project name or alias provided."))
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,30 @@
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+use crate::branch::Branch;
+use crate::config::expand_home;
+use crate::db::project::Project;
+
+/// Prints nothing but the project's directory, so shells can `cd "$(devinit cd <project>)"`.
+/// Projects without a root or working_directory print nothing at all.
+#[derive(Debug)]
+pub struct CdCommand {
+    pub alias: String,
+}
+
+impl Branch for CdCommand {
+    fn execute(&self) -> Result<()> {
+        let project = Project::resolve(&self.alias)?;
+        let config = project.config()?;
+
+        let Some(directory) = config.directory().map(expand_home) else { return Ok(()) };
+
+        if !Path::new(&directory).is_dir() {
+            return Err(anyhow!("{} is not a directory.", directory));
+        }
+
+        println!("{}", directory);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+use anyhow::Result;
+use clap::{Arg, Command, CommandFactory};
+use std::io;
+
+use crate::branch::Branch;
+use crate::cli::{Cli, Shell};
+use crate::db::{project::Project, alias::Alias, settings::REGISTRY, template::Template};
+
+/// Hooks the static completions clap generates up to `devinit __complete`, so project names and
+/// aliases come from the database. A wrapper falls back to the static completion when nothing matched.
+/// The `goto` function of `devinit shell-init` takes the same arguments, so it completes the same way.
+const BASH_DYNAMIC: &str = r#"
+_devinit_dynamic() {
+    local candidates
+    candidates="$(devinit __complete bash "${COMP_WORDS[@]:0:COMP_CWORD+1}" 2>/dev/null)"
+
+    if [[ -n "$candidates" ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$candidates" -- "${COMP_WORDS[COMP_CWORD]}"))
+    else
+        _devinit "$@"
+    fi
+}
+
+complete -F _devinit_dynamic -o bashdefault -o default devinit
+complete -F _devinit_dynamic -o bashdefault -o default goto
+"#;
+
+const ZSH_DYNAMIC: &str = r#"
+_devinit_dynamic() {
+    local -a candidates
+    candidates=("${(@f)$(devinit __complete zsh "${(@)words[1,CURRENT]}" 2>/dev/null)}")
+
+    if [[ -n "${candidates[1]}" ]]; then
+        compadd -a candidates
+    else
+        _devinit "$@"
+    fi
+}
+
+compdef _devinit_dynamic devinit
+compdef _devinit_dynamic goto
+"#;
+
+const FISH_DYNAMIC: &str = r#"
+complete -c devinit -f -n 'count (devinit __complete fish (commandline -opc) (commandline -ct)) >/dev/null' -a '(devinit __complete fish (commandline -opc) (commandline -ct))'
+complete -c goto --wraps devinit
+"#;
+
+#[derive(Debug)]
+pub struct CompletionsCommand {
+    pub shell: Shell,
+}
+
+impl Branch for CompletionsCommand {
+    fn execute(&self) -> Result<()> {
+        let (shell, dynamic) = match self.shell {
+            Shell::Bash => (clap_complete::Shell::Bash, BASH_DYNAMIC),
+            Shell::Zsh => (clap_complete::Shell::Zsh, ZSH_DYNAMIC),
+            Shell::Fish => (clap_complete::Shell::Fish, FISH_DYNAMIC),
+        };
+
+        clap_complete::generate(shell, &mut Cli::command(), "devinit", &mut io::stdout());
+        print!("{}", dynamic);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteCommand {
+    pub shell: Shell,
+    pub words: Vec<String>,
+}
+
+impl Branch for CompleteCommand {
+    fn execute(&self) -> Result<()> {
+        for candidate in self.candidates()? {
+            println!("{}", candidate);
+        }
+
+        Ok(())
+    }
+}
+
+/// Where the last word of a command line goes.
+struct Target {
+    /// The subcommand the word belongs to.
+    command: Command,
+    /// The value name of the argument the word fills, if it fills one.
+    value_name: Option<String>,
+    /// How many positionals came before the word.
+    positionals: usize,
+}
+
+impl Target {
+    /// Follows subcommands and option values up to the last word, or None when that word is
+    /// an option, which the static completion knows better.
+    fn of(words: &[String]) -> Option<Target> {
+        let (current, previous) = words.split_last()?;
+
+        let mut command = Cli::command();
+        let mut positionals = 0;
+        let mut pending: Option<Arg> = None;
+
+        // Skip the program name and follow subcommands and option values up to the current word
+        for word in previous.iter().skip(1) {
+            if pending.take().is_some() {
+                continue;
+            }
+
+            if word.starts_with('-') && word.len() > 1 {
+                pending = CompleteCommand::option(&command, word).filter(|arg| arg.get_action().takes_values()).cloned();
+                continue;
+            }
+
+            if positionals == 0 && let Some(subcommand) = command.find_subcommand(word) {
+                command = subcommand.clone();
+                continue;
+            }
+
+            positionals += 1;
+        }
+
+        let arg = match pending {
+            Some(arg) => Some(arg),
+            None if current.starts_with('-') => return None,
+            None => command.get_positionals().nth(positionals).cloned(),
+        };
+
+        let value_name = arg
+            .as_ref()
+            .and_then(|arg| arg.get_value_names())
+            .and_then(|names| names.first())
+            .map(|name| name.to_string());
+
+        Some(Target { command, value_name, positionals })
+    }
+
+    /// The subcommands that can go where a project can too. Bash and zsh only see the dynamic
+    /// list there, fish merges it with its static completions, which already name them.
+    fn subcommands(&self, shell: Shell) -> Vec<String> {
+        if self.positionals > 0 || matches!(shell, Shell::Fish) {
+            return Vec::new();
+        }
+
+        self.command
+            .get_subcommands()
+            .filter(|subcommand| !subcommand.is_hide_set())
+            .map(|subcommand| subcommand.get_name().to_string())
+            .collect()
+    }
+}
+
+impl CompleteCommand {
+    /// Lists what can go in the last word, or nothing when the static completion knows better.
+    fn candidates(&self) -> Result<Vec<String>> {
+        let Some(current) = self.words.last() else { return Ok(Vec::new()) };
+        let Some(target) = Target::of(&self.words) else { return Ok(Vec::new()) };
+
+        let mut candidates = Vec::new();
+
+        match target.value_name.as_deref() {
+            Some("PROJECT") => {
+                candidates.extend(Project::get_all()?.into_iter().map(|project| project.name));
+                candidates.extend(Alias::get_all()?.into_iter().map(|alias| alias.alias));
+            }
+            Some("ALIAS") => candidates.extend(Alias::get_all()?.into_iter().map(|alias| alias.alias)),
+            Some("SETTING") => candidates.extend(REGISTRY.iter().map(|setting| setting.key.to_string())),
+            Some("TEMPLATE") => candidates.extend(Template::get_all()?.into_iter().map(|template| template.name)),
+            _ => return Ok(Vec::new()),
+        }
+
+        candidates.extend(target.subcommands(self.shell));
+        candidates.retain(|candidate| candidate.starts_with(current.as_str()));
+        candidates.sort();
+        candidates.dedup();
+
+        Ok(candidates)
+    }
+
+    /// Finds the option a word like `--alias`, `--alias=x` or `-a` refers to.
+    fn option<'a>(command: &'a Command, word: &str) -> Option<&'a Arg> {
+        if let Some(long) = word.strip_prefix("--") {
+            if long.contains('=') {
+                return None;
+            }
+
+            return command.get_arguments().find(|arg| arg.get_long() == Some(long));
+        }
+
+        let short = word.chars().nth(1)?;
+        command.get_arguments().find(|arg| arg.get_short() == Some(short))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(words: &[&str]) -> Option<Target> {
+        Target::of(&words.iter().map(|word| word.to_string()).collect::<Vec<_>>())
+    }
+
+    fn value_name(words: &[&str]) -> Option<String> {
+        target(words).and_then(|target| target.value_name)
+    }
+
+    #[test]
+    fn completes_positionals_of_subcommands() {
+        assert_eq!(value_name(&["devinit", "view", ""]).as_deref(), Some("PROJECT"));
+        assert_eq!(value_name(&["devinit", "set", ""]).as_deref(), Some("SETTING"));
+        assert_eq!(value_name(&["devinit", "diff", "web", ""]).as_deref(), Some("REV"));
+    }
+
+    #[test]
+    fn completes_projects_and_subcommands_at_the_top() {
+        let target = target(&["goto", "we"]).unwrap();
+
+        assert_eq!(target.value_name.as_deref(), Some("PROJECT"));
+        assert!(target.subcommands(Shell::Bash).contains(&"view".to_string()));
+        assert!(target.subcommands(Shell::Fish).is_empty());
+
+        // Past the project there is nothing left to complete
+        assert_eq!(value_name(&["devinit", "web", ""]), None);
+    }
+
+    #[test]
+    fn completes_option_values() {
+        assert_eq!(value_name(&["devinit", "remove", "--alias", ""]).as_deref(), Some("ALIAS"));
+        assert_eq!(value_name(&["devinit", "add", "-t", ""]).as_deref(), Some("TEMPLATE"));
+        assert_eq!(value_name(&["devinit", "remove", "--alias", "w", ""]).as_deref(), Some("PROJECT"));
+
+        // A value given inline does not swallow the next word
+        assert_eq!(value_name(&["devinit", "remove", "--alias=w", ""]).as_deref(), Some("PROJECT"));
+    }
+
+    #[test]
+    fn leaves_options_to_the_static_completion() {
+        assert!(target(&["devinit", "view", "--"]).is_none());
+        assert!(target(&["devinit", "remove", "-"]).is_none());
+    }
+}
@@ -1,14 +1,22 @@
-use anyhow::{Result, Context};
-use std::process::Stdio;
-use std::process::Command;
-use std::io::Write;
+use anyhow::{anyhow, Result};
+use std::time::Instant;
 
 use crate::branch::Branch;
 use crate::db::{
+    launch::Launch,
     project::Project,
+    running::Running,
     settings::Settings
 };
-use crate::config::{ProjectConfig, Program};
+use crate::config::{Program, ProjectConfig};
+use crate::picker::Picker;
+use crate::process::{
+    ProcessManager,
+    attach::Session,
+    compose::Compose,
+    hooks::{HookContext, HookEvent},
+    ports::Ports
+};
 
 #[derive(Debug)]
 pub struct DefaultCommand {
@@ -17,108 +25,138 @@ pub struct DefaultCommand {
 
 impl Branch for DefaultCommand {
     fn execute(&self) -> Result<()> {
-        // Ensure alias is provided
-        let alias = self.alias
-            .as_ref()
-            .context("No alias input.")?;
+        // Fetch the project based on the alias, or let the user pick one
+        let project: Project = match &self.alias {
+            Some(alias) => Project::resolve(alias)?,
+            None => Picker::project("Project to launch")?,
+        };
+        let alias = self.alias.as_deref().unwrap_or(&project.name);
 
-        // Fetch the project based on the alias
-        let project: Project = Project::get(&[alias.to_string()]).context("Failed to fetch project.")?;
+        let started = Instant::now();
 
         // Deserialize the TOML configuration
-        let config: ProjectConfig = toml::from_str(&project.toml)
-            .context("Failed to deserialize TOML configuration.")?;
+        let result = project.config()
+            .and_then(|config: ProjectConfig| project.execute(config, None));
 
-        project.execute(config)?;
+        Launch::add(project.id, alias, started.elapsed(), result.is_ok())?;
 
-        Ok(())
+        result
     }
 }
 
-struct ProcessManager;
+impl Project {
+    /// Launches every program, into the attached session when one is given.
+    pub fn execute(&self, config: ProjectConfig, mut session: Option<&mut Session>) -> Result<()> {
+        let global = Settings::get_hooks()?;
+        let hooks = config.hooks.unwrap_or_default();
+        let context = HookContext::project(&self.name);
 
-impl ProcessManager {
-    fn run_program(program: &Program) -> Result<()> {
-        let mut cmd: Command = 
-            if let Some(settings) = &program.settings {
-                if settings.uwsm {
-                    let mut temp = Command::new("uwsm");
-                    temp.args(["app", "--", &program.path]);
-                    temp
-                }
-                else {
-                    Command::new(&program.path)
-                }
-            }
-            else {
-                Command::new(&program.path)
-            };
-        
-
-        // Set working directory
-        if let Some(dir) = &program.working_directory {
-            cmd.current_dir(dir);
-        }
-    
-        // Add arguments
-        if let Some(args) = &program.args {
-            cmd.args(args);
-        }
-    
-        // Add environment variables
-        if let Some(env_vars) = &program.env {
-            for (key, value) in env_vars {
-                cmd.env(key, value);
+        // Global hooks wrap project hooks, which wrap program hooks
+        let mut result = global.run(HookEvent::PreLaunch, &context)
+            .and_then(|_| hooks.run(HookEvent::PreLaunch, &context));
+
+        for program in config.programs.list {
+            if result.is_err() {
+                break;
             }
+
+            result = self.launch(program, &context, session.as_deref_mut());
         }
 
-        // Handle output mode
-        match program.output_mode.as_deref() {
-            Some("null") => {
-                cmd.stdout(Stdio::null());
-                cmd.stderr(Stdio::null());
-            }
-            Some("inherit") => {
-                cmd.stdout(Stdio::inherit());
-                cmd.stderr(Stdio::inherit());
+        match &result {
+            Ok(()) => {
+                hooks.run(HookEvent::PostLaunch, &context)?;
+                global.run(HookEvent::PostLaunch, &context)?;
             }
-            Some("log") => {
-                // TODO: Implement logging to a file
-                cmd.stdout(Stdio::piped());
-                cmd.stderr(Stdio::piped());
+            Err(_) => {
+                let context = context.exited(1);
+                hooks.run(HookEvent::OnFailure, &context)?;
+                global.run(HookEvent::OnFailure, &context)?;
             }
-            _ => {}
         }
-    
-        // Execute the command
-        let mut child = cmd
-            .stdin(Stdio::piped())
-            .spawn()
-            .context("Failed to execute program:")?;
-
-        // Write commands to stdin
-        if let Some(commands) = &program.commands {
-            if let Some(mut stdin) = child.stdin.take() {
-                for command in commands {
-                    stdin.write(format!("{}\n", command).as_bytes())?;
-                    stdin.flush()?;
+
+        result
+    }
+
+    fn launch(&self, mut program: Program, context: &HookContext, session: Option<&mut Session>) -> Result<()> {
+        if program.settings.is_none() {
+            program.settings = Some(Settings::get_all()?);
+        }
+
+        if !Ports::resolve_conflicts(&program)? {
+            println!("Skipping {}.", program.name);
+            return Ok(());
+        }
+
+        let hooks = program.hooks.take().unwrap_or_default();
+        let context = context.program(&program.name);
+
+        let result = hooks.run(HookEvent::PreLaunch, &context)
+            .and_then(|_| self.start(&program, session));
+
+        match &result {
+            Ok(()) => hooks.run(HookEvent::PostLaunch, &context)?,
+            Err(_) => hooks.run(HookEvent::OnFailure, &context.exited(1))?,
+        }
+
+        result
+    }
+
+    fn start(&self, program: &Program, session: Option<&mut Session>) -> Result<()> {
+        match (program.kind.as_deref(), session) {
+            (None | Some("process"), Some(session)) => session.spawn(program),
+            (None | Some("process"), None) => {
+                let pid = ProcessManager::run_program(program)?;
+
+                Running::add(self.id, &program.name, Some(pid as i32), None)
+            }
+            (Some("compose"), session) => {
+                let compose = Compose::new(program);
+
+                compose.up()?;
+
+                match session {
+                    Some(session) => {
+                        session.teardown(&program.name, compose.down_command());
+                        Ok(())
+                    }
+                    None => Running::add(self.id, &program.name, None, Some(compose.down_command())),
                 }
             }
+            (Some(other), _) => Err(anyhow!("Unknown kind {} for program {}.", other, program.name)),
         }
-    
-        Ok(())      
     }
-}
 
-impl Project {
-    fn execute(&self, config: ProjectConfig) -> Result<()> {
-        for mut program in config.programs.list {
-            if program.settings.is_none() {
-                program.settings = Some(Settings::get_all()?);
-            }
+    /// Runs on_exit hooks once a project's programs have exited or been stopped.
+    pub fn exited(&self, programs: &[(String, i32)], code: i32) -> Result<()> {
+        self.programs_exited(programs)?;
 
-            ProcessManager::run_program(&program)?;
+        let global = Settings::get_hooks()?;
+        let context = HookContext::project(&self.name).exited(code);
+
+        if let Some(hooks) = self.config().ok().and_then(|config| config.hooks) {
+            hooks.run(HookEvent::OnExit, &context)?;
+        }
+
+        global.run(HookEvent::OnExit, &context)
+    }
+
+    /// Runs the on_exit hooks of the programs alone, for when others of the project still run.
+    pub fn programs_exited(&self, programs: &[(String, i32)]) -> Result<()> {
+        let Ok(config) = self.config() else { return Ok(()) };
+        let context = HookContext::project(&self.name);
+
+        for (name, code) in programs {
+            let hooks = config.programs.list
+                .iter()
+                .find(|program| &program.name == name)
+                .and_then(|program| program.hooks.as_ref());
+
+            if let Some(hooks) = hooks {
+                hooks.run(HookEvent::OnExit, &context.program(name).exited(*code))?;
+            }
         }
+
         Ok(())
     }
-}
\ No newline at end of file
+}
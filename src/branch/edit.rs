@@ -3,18 +3,22 @@ use anyhow::Result;
 use crate::branch::Branch;
 use crate::db::project::Project;
 use crate::config::Editor;
+use crate::picker::Picker;
 
 #[derive(Debug)]
 pub struct EditCommand {
-    pub alias: String,
+    pub alias: Option<String>,
 }
 
 impl Branch for EditCommand {
     fn execute(&self) -> Result<()> {
-        let project = Project::get(&[self.alias.to_string()])?;
+        let project = match &self.alias {
+            Some(alias) => Project::resolve(alias)?,
+            None => Picker::project("Project to edit")?,
+        };
 
-        let new_toml = Editor::input_editor(&project.toml)?;
+        let new_toml = Editor::input_config(&project.name, &project.toml)?;
 
-        Project::replace_toml(project.id, new_toml) 
+        Project::replace_toml(project.id, new_toml, "edit")
     }
 }
\ No newline at end of file
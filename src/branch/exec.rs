@@ -0,0 +1,43 @@
+use anyhow::{anyhow, Result, Context};
+use std::process::Command;
+
+use crate::branch::Branch;
+use crate::config::expand_home;
+use crate::db::project::Project;
+use crate::process::attach::wait_foreground;
+use crate::ExecCommand;
+
+impl Branch for ExecCommand {
+    fn execute(&self) -> Result<()> {
+        let project = Project::resolve(&self.alias)?;
+        let config = project.config()?;
+
+        let program = config.program(self.program.as_deref())?;
+
+        let (path, args) = self.command
+            .split_first()
+            .ok_or_else(|| anyhow!("No command given to run."))?;
+
+        let mut cmd = Command::new(path);
+        cmd.args(args)
+            .envs(program.environment()?)
+            .env("DEVINIT_PROJECT", &project.name)
+            .env("DEVINIT_PROGRAM", &program.name);
+
+        if let Some(dir) = program.working_directory.as_deref().or(config.directory()) {
+            cmd.current_dir(expand_home(dir));
+        }
+
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to run {}.", path))?;
+
+        let code = wait_foreground(&mut child)?;
+
+        if code != 0 {
+            std::process::exit(code);
+        }
+
+        Ok(())
+    }
+}
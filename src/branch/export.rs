@@ -0,0 +1,82 @@
+use anyhow::{Result, Context};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::ExportCommand;
+use crate::branch::Branch;
+use crate::db::{project::Project, alias::Alias, launch::Launch};
+use crate::import::bundle::{Bundle, Exported};
+
+impl ExportCommand {
+    fn projects(&self) -> Result<Vec<Project>> {
+        if self.projects.is_empty() {
+            return Project::get_all();
+        }
+
+        let mut projects: Vec<Project> = Vec::new();
+
+        for name in &self.projects {
+            let project = Project::resolve(name)?;
+
+            if !projects.iter().any(|exported| exported.id == project.id) {
+                projects.push(project);
+            }
+        }
+
+        Ok(projects)
+    }
+}
+
+impl Branch for ExportCommand {
+    fn execute(&self) -> Result<()> {
+        let aliases = Alias::get_all()?;
+        let mut launches = Launch::recorded()?;
+        let exported: Vec<Exported> = self.projects()?
+            .into_iter()
+            .map(|project| Exported::of(project, &aliases, &mut launches))
+            .collect();
+
+        let count = exported.len();
+
+        if let Some(dir) = &self.dir {
+            let dir = Path::new(dir);
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory {}.", dir.display()))?;
+
+            let mut stems: HashSet<String> = HashSet::new();
+
+            for project in exported {
+                // Project names are free text, keep them from escaping the directory
+                let stem = project.name.replace(['/', '\\'], "_");
+
+                // Names like a/b and a_b end up the same, number the later ones
+                let stem = (1..)
+                    .map(|number| if number == 1 { stem.to_string() } else { format!("{}-{}", stem, number) })
+                    .find(|candidate| stems.insert(candidate.to_string()))
+                    .unwrap_or_default();
+
+                let file = dir.join(format!("{}.toml", stem));
+                let bundle = Bundle { projects: vec![project] };
+
+                fs::write(&file, bundle.to_toml()?)
+                    .with_context(|| format!("Failed to write {}.", file.display()))?;
+            }
+
+            println!("Exported {} projects to {}.", count, dir.display());
+            return Ok(());
+        }
+
+        let bundle = Bundle { projects: exported }.to_toml()?;
+
+        match &self.bundle {
+            Some(file) => {
+                fs::write(file, bundle).with_context(|| format!("Failed to write {}.", file))?;
+                println!("Exported {} projects to {}.", count, file);
+            }
+            None => print!("{}", bundle),
+        }
+
+        Ok(())
+    }
+}
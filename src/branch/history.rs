@@ -0,0 +1,47 @@
+use anyhow::Result;
+
+use crate::branch::Branch;
+use crate::db::{project::Project, launch::Launch};
+
+/// How many launches `history` shows.
+const HISTORY_LIMIT: u32 = 25;
+
+#[derive(Debug)]
+pub struct HistoryCommand {
+    pub alias: Option<String>,
+}
+
+impl Branch for HistoryCommand {
+    fn execute(&self) -> Result<()> {
+        let id = match &self.alias {
+            Some(alias) => Some(Project::resolve(alias)?.id),
+            None => None,
+        };
+
+        let launches = Launch::history(id, HISTORY_LIMIT)?;
+
+        if launches.is_empty() {
+            println!("No launches recorded yet.");
+            return Ok(());
+        }
+
+        for launch in launches {
+            let alias = if launch.alias == launch.project {
+                String::new()
+            } else {
+                format!(" (as {})", launch.alias)
+            };
+
+            println!(
+                "{}  {}{}  {:.1}s  {}",
+                launch.time,
+                launch.project,
+                alias,
+                launch.duration as f64 / 1000.0,
+                if launch.success { "ok" } else { "failed" }
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,375 @@
+use anyhow::{anyhow, Result, Context};
+use dialoguer::Confirm;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::{Conflict, ImportCommand, ImportFormat};
+use crate::branch::Branch;
+use crate::db::{DB, project::Project, alias::Alias, launch::Launch};
+use crate::import::{procfile, tmuxinator, tmuxp};
+use crate::import::bundle::{Bundle, Exported};
+
+/// What importing an exported project will do.
+enum Action {
+    Add(String),
+    Overwrite(i32),
+    Skip(String),
+}
+
+struct Step {
+    project: Exported,
+    action: Action,
+    /// The project's aliases that are free to take.
+    aliases: Vec<String>,
+    /// Aliases left out because another project has them.
+    taken: Vec<String>,
+}
+
+impl Branch for ImportCommand {
+    fn execute(&self) -> Result<()> {
+        match self.from {
+            Some(format) => self.import_foreign(format),
+            None => import_bundle(Bundle::read(Path::new(&self.file))?, self.conflict, self.yes, &self.file),
+        }
+    }
+}
+
+impl ImportCommand {
+    /// Imports a single project from another tool's configuration.
+    fn import_foreign(&self, format: ImportFormat) -> Result<()> {
+        let path = Path::new(&self.file);
+
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}.", self.file))?;
+
+        // Relative directories in the file are relative to where it lives
+        let base = fs::canonicalize(path)?
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let imported = match format {
+            ImportFormat::Procfile => procfile::parse(&contents, &base)?,
+            ImportFormat::Tmuxinator => tmuxinator::parse(&contents, &base)?,
+            ImportFormat::Tmuxp => tmuxp::parse(&contents, &base)?,
+        };
+
+        let name = self.name
+            .clone()
+            .or(imported.name)
+            .or_else(|| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+            .ok_or(anyhow!("No project name given or found in the file."))?;
+
+        if Project::get_id(name.to_string()).is_ok() {
+            return Err(anyhow!("A project or alias named {} already exists.", name));
+        }
+
+        for alias in &self.alias {
+            if Alias::check(alias.to_string())? {
+                return Err(anyhow!("Alias {} already exists.", alias));
+            }
+        }
+
+        let toml = toml::to_string(&imported.config)
+            .context("Failed to serialize imported configuration.")?;
+
+        let proj_id = Project::add(name.to_string(), toml)?;
+
+        if !self.alias.is_empty() {
+            Alias::add_all(proj_id, self.alias.clone())?;
+        }
+
+        println!(
+            "Imported project {} with {} programs.",
+            name,
+            imported.config.programs.list.len()
+        );
+
+        Ok(())
+    }
+}
+
+/// Imports the projects of a bundle read from `source`, after previewing what happens to each.
+/// Everything is stored in one transaction, so a failure leaves the database as it was.
+pub fn import_bundle(bundle: Bundle, conflict: Conflict, yes: bool, source: &str) -> Result<()> {
+    let steps = plan(bundle, conflict, &Project::get_all()?, &Alias::get_all()?);
+
+    if steps.is_empty() {
+        println!("{} has no projects to import.", source);
+        return Ok(());
+    }
+
+    let width = steps.iter().map(|step| step.project.name.len()).max().unwrap_or(0);
+
+    for step in &steps {
+        let action = match &step.action {
+            Action::Add(name) if *name == step.project.name => "add".to_string(),
+            Action::Add(name) => format!("add as {}", name),
+            Action::Overwrite(_) => "overwrite".to_string(),
+            Action::Skip(reason) => format!("skip, {}", reason),
+        };
+
+        let taken = match (&step.action, step.taken.is_empty()) {
+            (Action::Skip(_), _) | (_, true) => String::new(),
+            _ => format!(" (aliases already taken: {})", step.taken.join(", ")),
+        };
+
+        println!("{:width$}  {}{}", step.project.name, action, taken, width = width);
+    }
+
+    let count = steps.iter().filter(|step| !matches!(step.action, Action::Skip(_))).count();
+
+    if count == 0 {
+        println!("Nothing to import.");
+        return Ok(());
+    }
+
+    if !yes && !Confirm::new()
+        .with_prompt(format!("Import {} projects?", count))
+        .default(true)
+        .interact()?
+    {
+        return Ok(());
+    }
+
+    DB::transaction(|conn| {
+        for step in steps {
+            match step.action {
+                Action::Add(name) => {
+                    let id = Project::add_in(conn, name, step.project.toml)?;
+                    Alias::add_all_in(conn, id, step.aliases)?;
+                    Launch::add_recorded_in(conn, id, &step.project.launches)?;
+                }
+                // The project keeps its own launch history, importing it again would count it twice
+                Action::Overwrite(id) => {
+                    Project::replace_toml_in(conn, id, step.project.toml, "import")?;
+                    Alias::add_all_in(conn, id, step.aliases)?;
+                }
+                Action::Skip(_) => {}
+            }
+        }
+
+        Ok(())
+    })
+    .context("Failed to import, nothing was imported.")?;
+
+    println!("Imported {} projects.", count);
+
+    Ok(())
+}
+
+/// Decides what happens to each exported project under the conflict strategy, given the
+/// projects and aliases there are. Names taken earlier in the same import count as taken
+/// too, and projects whose configuration is invalid are skipped.
+fn plan(bundle: Bundle, conflict: Conflict, projects: &[Project], aliases: &[Alias]) -> Vec<Step> {
+    // Bases of extends can come with the import or already be there
+    let bundled: Vec<(String, String)> = bundle.projects
+        .iter()
+        .map(|project| (project.name.to_string(), project.toml.to_string()))
+        .collect();
+
+    let find = |name: &str| {
+        let found = bundled
+            .iter()
+            .find(|(bundled, _)| bundled == name)
+            .map(|(name, toml)| (0, name, toml))
+            .or_else(|| {
+                let id = aliases.iter().find(|alias| alias.alias == name).map(|alias| alias.id);
+
+                projects
+                    .iter()
+                    .find(|project| project.name == name || Some(project.id) == id)
+                    .map(|project| (project.id, &project.name, &project.toml))
+            });
+
+        found
+            .map(|(id, name, toml)| Project { id, name: name.to_string(), toml: toml.to_string() })
+            .ok_or_else(|| anyhow!("No project or alias named {}.", name))
+    };
+
+    let mut taken: HashSet<String> = projects
+        .iter()
+        .map(|project| project.name.to_string())
+        .chain(aliases.iter().map(|alias| alias.alias.to_string()))
+        .collect();
+    let mut overwritten: HashSet<i32> = HashSet::new();
+
+    let mut steps = Vec::new();
+
+    for project in bundle.projects {
+        let existing = projects.iter().find(|existing| existing.name == project.name);
+
+        let invalid = Project { id: 0, name: project.name.to_string(), toml: project.toml.to_string() }
+            .config_using(&find)
+            .err();
+
+        let action = if let Some(error) = invalid {
+            let cause = error.root_cause().to_string();
+            Action::Skip(format!("invalid configuration: {}", cause.lines().next().unwrap_or_default()))
+        } else if !taken.contains(&project.name) {
+            Action::Add(project.name.to_string())
+        } else {
+            match (conflict, existing) {
+                (Conflict::Skip, _) => Action::Skip("the name is taken".to_string()),
+                (Conflict::Overwrite, Some(existing)) if overwritten.insert(existing.id) => Action::Overwrite(existing.id),
+                (Conflict::Overwrite, Some(_)) => Action::Skip("it was already imported".to_string()),
+                (Conflict::Overwrite, None) => Action::Skip("the name is an alias of another project".to_string()),
+                (Conflict::Rename, _) => Action::Add(free_name(&project.name, &taken)),
+            }
+        };
+
+        let own: Vec<&str> = match &action {
+            Action::Overwrite(id) => aliases
+                .iter()
+                .filter(|alias| alias.id == *id)
+                .map(|alias| alias.alias.as_str())
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        // Aliases the overwritten project already has need no adding
+        let (free, busy): (Vec<String>, Vec<String>) = project.aliases
+            .iter()
+            .filter(|alias| !own.contains(&alias.as_str()))
+            .cloned()
+            .partition(|alias| !taken.contains(alias));
+
+        if let Action::Add(name) = &action {
+            taken.insert(name.to_string());
+        }
+
+        if !matches!(action, Action::Skip(_)) {
+            taken.extend(free.iter().cloned());
+        }
+
+        steps.push(Step { project, action, aliases: free, taken: busy });
+    }
+
+    steps
+}
+
+/// The first of name-2, name-3, ... that is not taken.
+fn free_name(name: &str, taken: &HashSet<String>) -> String {
+    (2..)
+        .map(|number| format!("{}-{}", name, number))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: &str = "[[programs.list]]\nname = \"server\"\npath = \"cargo\"\n";
+
+    fn existing() -> (Vec<Project>, Vec<Alias>) {
+        let projects = vec![
+            Project { id: 1, name: "web".to_string(), toml: VALID.to_string() },
+            Project { id: 2, name: "api".to_string(), toml: VALID.to_string() },
+        ];
+        let aliases = vec![
+            Alias { id: 1, alias: "w".to_string() },
+            Alias { id: 2, alias: "backend".to_string() },
+        ];
+
+        (projects, aliases)
+    }
+
+    fn exported(name: &str, aliases: &[&str], toml: &str) -> Exported {
+        Exported {
+            name: name.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            toml: toml.to_string(),
+            launches: Vec::new(),
+        }
+    }
+
+    fn bundle() -> Bundle {
+        Bundle {
+            projects: vec![
+                exported("web", &["w", "site"], VALID),
+                exported("shop", &["site", "s"], VALID),
+                exported("backend", &[], VALID),
+                exported("web", &[], VALID),
+            ],
+        }
+    }
+
+    fn actions(steps: &[Step]) -> Vec<String> {
+        steps
+            .iter()
+            .map(|step| match &step.action {
+                Action::Add(name) => format!("add {}", name),
+                Action::Overwrite(id) => format!("overwrite {}", id),
+                Action::Skip(reason) => format!("skip, {}", reason),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn skips_taken_names() {
+        let (projects, aliases) = existing();
+        let steps = plan(bundle(), Conflict::Skip, &projects, &aliases);
+
+        assert_eq!(actions(&steps), [
+            "skip, the name is taken",
+            "add shop",
+            "skip, the name is taken",
+            "skip, the name is taken",
+        ]);
+
+        // site was not taken by the skipped web, so shop gets it
+        assert_eq!(steps[1].aliases, ["site", "s"]);
+    }
+
+    #[test]
+    fn overwrites_projects_once() {
+        let (projects, aliases) = existing();
+        let steps = plan(bundle(), Conflict::Overwrite, &projects, &aliases);
+
+        assert_eq!(actions(&steps), [
+            "overwrite 1",
+            "add shop",
+            "skip, the name is an alias of another project",
+            "skip, it was already imported",
+        ]);
+
+        // web already has w, and shop comes too late for site
+        assert_eq!(steps[0].aliases, ["site"]);
+        assert!(steps[0].taken.is_empty());
+        assert_eq!(steps[1].aliases, ["s"]);
+        assert_eq!(steps[1].taken, ["site"]);
+    }
+
+    #[test]
+    fn renames_taken_names() {
+        let (projects, aliases) = existing();
+        let steps = plan(bundle(), Conflict::Rename, &projects, &aliases);
+
+        assert_eq!(actions(&steps), ["add web-2", "add shop", "add backend-2", "add web-3"]);
+        assert_eq!(steps[0].taken, ["w"]);
+    }
+
+    #[test]
+    fn skips_invalid_configurations() {
+        let (projects, aliases) = existing();
+        let bundle = Bundle {
+            projects: vec![
+                exported("typo", &[], "[[programs.list]]\nname = \"server\"\npaht = \"cargo\"\n"),
+                exported("child", &[], "extends = \"base\"\n"),
+                exported("base", &[], VALID),
+                exported("orphan", &[], "extends = \"gone\"\n"),
+                exported("alias-child", &[], "extends = \"backend\"\n"),
+            ],
+        };
+
+        let actions = actions(&plan(bundle, Conflict::Skip, &projects, &aliases));
+
+        assert!(actions[0].starts_with("skip, invalid configuration: unknown field `paht`"), "{}", actions[0]);
+        assert_eq!(actions[1], "add child");
+        assert_eq!(actions[2], "add base");
+        assert_eq!(actions[3], "skip, invalid configuration: No project or alias named gone.");
+        assert_eq!(actions[4], "add alias-child");
+    }
+}
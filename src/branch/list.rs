@@ -2,15 +2,18 @@ use anyhow::Result;
 use std::collections::HashMap;
 
 use crate::branch::Branch;
-use crate::db::{project::Project, alias::Alias};
+use crate::db::{project::Project, alias::Alias, launch::Launch};
 
 pub struct ListCommand {}
 
 impl Branch for ListCommand {
     fn execute(&self) -> Result<()> {
-        println!("Viewing all projects:\n");
+        let projects = Project::get_ranked()?;
 
-        let projects = Project::get_all()?;
+        if projects.is_empty() {
+            println!("No projects yet, add one with `devinit add <name>`.");
+            return Ok(());
+        }
 
         let aliases = Alias::get_all()?;
 
@@ -19,18 +22,29 @@ impl Branch for ListCommand {
             alias_map.entry(alias.id).or_default().push(alias.alias);
         }
 
-        for project in projects {
-            println!("Project Name: {}", project.name);
+        let usage = Launch::usage()?;
 
-            if let Some(project_aliases) = alias_map.get(&project.id) {
-                println!("Aliases: {}", project_aliases.join(", "));
-            } else {
-                println!("Aliases: None");
-            }
+        let width = projects.iter().map(|project| project.name.len()).max().unwrap_or(0);
 
-            println!("TOML Content:\n{}\n", project.toml);
+        for project in projects {
+            let aliases = alias_map
+                .get(&project.id)
+                .map(|aliases| aliases.join(", "))
+                .unwrap_or_else(|| "-".to_string());
+
+            let launches = match usage.get(&project.id) {
+                Some(usage) => format!(
+                    "{} launch{}, last {}",
+                    usage.count,
+                    if usage.count == 1 { "" } else { "es" },
+                    Launch::ago(usage.last)
+                ),
+                None => "never launched".to_string(),
+            };
+
+            println!("{:width$}  aliases: {}  ({})", project.name, aliases, launches, width = width);
         }
 
         Ok(())
     }
-}
\ No newline at end of file
+}
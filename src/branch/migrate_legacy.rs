@@ -0,0 +1,25 @@
+use anyhow::{anyhow, Result};
+use directories::BaseDirs;
+use std::path::PathBuf;
+
+use crate::MigrateLegacyCommand;
+use crate::branch::Branch;
+use crate::branch::import::import_bundle;
+use crate::config::expand_home;
+use crate::import::legacy;
+
+impl Branch for MigrateLegacyCommand {
+    fn execute(&self) -> Result<()> {
+        let dir = match &self.dir {
+            Some(dir) => PathBuf::from(expand_home(dir)),
+            None => BaseDirs::new()
+                .ok_or(anyhow!("Could not determine the home directory."))?
+                .config_dir()
+                .join("development_initializer"),
+        };
+
+        let bundle = legacy::read(&dir)?;
+
+        import_bundle(bundle, self.conflict, self.yes, &dir.to_string_lossy())
+    }
+}
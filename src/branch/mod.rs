@@ -2,11 +2,26 @@ use anyhow::Result;
 
 
 pub(super) mod add;
+pub(super) mod cd;
+pub(super) mod completions;
 pub(super) mod default;
 pub(super) mod edit;
+pub(super) mod exec;
+pub(super) mod export;
+pub(super) mod history;
+pub(super) mod import;
 pub(super) mod list;
+pub(super) mod migrate_legacy;
 pub(super) mod remove;
+pub(super) mod revisions;
+pub(super) mod run;
+pub(super) mod schema;
 pub(super) mod set;
+pub(super) mod settings;
+pub(super) mod shell;
+pub(super) mod shell_init;
+pub(super) mod stop;
+pub(super) mod template;
 pub(super) mod view;
 
 pub trait Branch {
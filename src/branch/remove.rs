@@ -1,8 +1,9 @@
 use dialoguer::Confirm;
-use anyhow::{Result, anyhow};
+use anyhow::Result;
 
 use crate::RemoveCommand;
 use crate::branch::Branch;
+use crate::picker::Picker;
 use crate::db::{project::Project, alias::Alias};
 
 impl RemoveCommand {
@@ -18,18 +19,21 @@ impl RemoveCommand {
         }
     }
 
-    fn remove_proj(alias: String) -> Result<()>{
+    fn remove_proj(alias: Option<String>) -> Result<()>{
+        let project = match alias {
+            Some(alias) => Project::find(&alias)?,
+            None => Picker::project("Project to remove")?,
+        };
+
         let confirm = Confirm::new()
-            .with_prompt(format!("Are you sure you want to delete project {}?", &alias))
+            .with_prompt(format!("Are you sure you want to delete project {}?", &project.name))
             .interact()?;
 
         if !confirm { return Ok(()) }
 
-        let proj_id = Project::get_id(alias.to_string())?;
-
-        Project::remove(proj_id)?;
+        Project::remove(project.id)?;
 
-        println!("Successfully removed project {}", &alias);
+        println!("Successfully removed project {}", &project.name);
 
         Ok(())
     }
@@ -53,17 +57,17 @@ impl Branch for RemoveCommand {
     fn execute(&self) -> Result<()> {
         if let Some(project) = &self.project {
             // Explicit project name provided via --project
-            Self::remove_proj(project.to_string())
+            Self::remove_proj(Some(project.to_string()))
         } 
         else if let Some(proj_name) = &self.proj_name {
             // Implicit project name or alias provided as a positional argument
-            Self::remove_proj(proj_name.to_string())
+            Self::remove_proj(Some(proj_name.to_string()))
         } else if let Some(alias) = &self.alias {
             // Explicit alias provided via --alias
             Self::remove_alias(alias.to_string())
         } else {
-            // No valid input provided
-            return Err(anyhow!("No project name or alias provided."));
+            // Nothing given, pick the project interactively
+            Self::remove_proj(None)
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,101 @@
+use anyhow::{anyhow, Result};
+use similar::TextDiff;
+
+use crate::branch::Branch;
+use crate::db::{project::Project, revision::Revision};
+
+#[derive(Debug)]
+pub struct HistoryConfigCommand {
+    pub alias: String,
+}
+
+impl Branch for HistoryConfigCommand {
+    fn execute(&self) -> Result<()> {
+        let project = Project::resolve(&self.alias)?;
+        let revisions = Revision::get_all(project.id)?;
+
+        if revisions.is_empty() {
+            println!("{} has no revisions yet, they are kept whenever its configuration is replaced.", project.name);
+            return Ok(());
+        }
+
+        let width = revisions.len().to_string().len();
+
+        // Each revision is the configuration as it was until the reason replaced it
+        for revision in revisions.iter().rev() {
+            println!(
+                "{:>width$}  {}  replaced by {}",
+                revision.number,
+                revision.time,
+                revision.reason,
+                width = width
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct DiffCommand {
+    pub alias: String,
+    pub rev: Option<u32>,
+}
+
+impl Branch for DiffCommand {
+    fn execute(&self) -> Result<()> {
+        let project = Project::resolve(&self.alias)?;
+
+        let revision = match self.rev {
+            Some(number) => Revision::get(project.id, &project.name, number)?,
+            None => Revision::get_all(project.id)?
+                .pop()
+                .ok_or_else(|| anyhow!("{} has no revisions yet.", project.name))?,
+        };
+
+        if revision.toml == project.toml {
+            println!("Revision {} of {} matches its current configuration.", revision.number, project.name);
+            return Ok(());
+        }
+
+        let diff = TextDiff::from_lines(&revision.toml, &project.toml);
+
+        print!(
+            "{}",
+            diff.unified_diff()
+                .header(&format!("{} revision {}", project.name, revision.number), &format!("{} current", project.name))
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct RevertCommand {
+    pub alias: String,
+    pub rev: u32,
+}
+
+impl Branch for RevertCommand {
+    fn execute(&self) -> Result<()> {
+        let project = Project::resolve(&self.alias)?;
+        let revision = Revision::get(project.id, &project.name, self.rev)?;
+
+        if revision.toml == project.toml {
+            println!("{} already has the configuration of revision {}.", project.name, revision.number);
+            return Ok(());
+        }
+
+        Project::replace_toml(project.id, revision.toml, &format!("revert to {}", revision.number))?;
+
+        // Reverting kept the replaced configuration as the newest revision
+        let latest = Revision::get_all(project.id)?.len();
+
+        println!(
+            "Reverted {} to revision {}, `devinit revert {} {}` undoes it.",
+            project.name, revision.number, project.name, latest
+        );
+
+        Ok(())
+    }
+}
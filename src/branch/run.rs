@@ -0,0 +1,41 @@
+use anyhow::Result;
+use std::time::Instant;
+
+use crate::branch::Branch;
+use crate::db::{launch::Launch, project::Project};
+use crate::process::attach::Session;
+
+#[derive(Debug)]
+pub struct RunCommand {
+    pub alias: String,
+}
+
+impl Branch for RunCommand {
+    fn execute(&self) -> Result<()> {
+        let project = Project::resolve(&self.alias)?;
+
+        let started = Instant::now();
+
+        let config = project.config()?;
+
+        let mut session = Session::new(&config.programs.list);
+
+        if let Err(e) = project.execute(config, Some(&mut session)) {
+            session.abort()?;
+            Launch::add(project.id, &self.alias, started.elapsed(), false)?;
+            return Err(e);
+        }
+
+        let (exits, code) = session.wait()?;
+
+        Launch::add(project.id, &self.alias, started.elapsed(), code == 0)?;
+
+        project.exited(&exits, code)?;
+
+        if code != 0 {
+            std::process::exit(code);
+        }
+
+        Ok(())
+    }
+}
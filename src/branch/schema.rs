@@ -0,0 +1,29 @@
+use anyhow::{Result, Context};
+
+use crate::branch::Branch;
+use crate::config::{install_schema, schema};
+
+#[derive(Debug)]
+pub struct SchemaCommand {
+    pub taplo: bool,
+}
+
+impl Branch for SchemaCommand {
+    fn execute(&self) -> Result<()> {
+        if !self.taplo {
+            println!("{}", serde_json::to_string_pretty(&schema()).context("Failed to serialize the schema.")?);
+            return Ok(());
+        }
+
+        let path = install_schema()?;
+
+        // devinit opens configurations as devinit-*.toml temporary files
+        println!("[[rule]]");
+        println!("include = [\"**/devinit-*.toml\"]");
+        println!();
+        println!("[rule.schema]");
+        println!("path = \"file://{}\"", path.display());
+
+        Ok(())
+    }
+}
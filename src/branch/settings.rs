@@ -0,0 +1,74 @@
+use anyhow::{anyhow, Result};
+
+use crate::branch::Branch;
+use crate::db::settings::{Settings, Setting, REGISTRY};
+
+/// `devinit get <key>`, printing nothing but the value so scripts can use it.
+#[derive(Debug)]
+pub struct GetCommand {
+    pub key: String,
+}
+
+impl Branch for GetCommand {
+    fn execute(&self) -> Result<()> {
+        match Settings::get(&self.key)? {
+            Some(value) => println!("{}", value),
+            None => return Err(anyhow!("{} is not set.", self.key)),
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct UnsetCommand {
+    pub key: String,
+}
+
+impl Branch for UnsetCommand {
+    fn execute(&self) -> Result<()> {
+        Settings::unset(&self.key)?;
+
+        match Setting::find(&self.key)?.default {
+            Some(default) => println!("Unset {}, back to its default {}.", self.key, default),
+            None => println!("Unset {}.", self.key),
+        }
+
+        Ok(())
+    }
+}
+
+/// `devinit settings list`, every setting with its value, type and description.
+#[derive(Debug)]
+pub struct SettingsListCommand {}
+
+impl Branch for SettingsListCommand {
+    fn execute(&self) -> Result<()> {
+        let mut stored = Settings::stored_all()?;
+        let mut rows = Vec::new();
+
+        for setting in REGISTRY {
+            let value = match (stored.remove(setting.key), setting.default) {
+                (Some(value), _) => value,
+                (None, Some(default)) => format!("{} (default)", default),
+                (None, None) => "-".to_string(),
+            };
+
+            rows.push((setting, value));
+        }
+
+        let key_width = REGISTRY.iter().map(|setting| setting.key.len()).max().unwrap_or(0);
+        let value_width = rows.iter().map(|(_, value)| value.len()).max().unwrap_or(0);
+        let kind_width = REGISTRY.iter().map(|setting| setting.kind.name().len()).max().unwrap_or(0);
+
+        for (setting, value) in rows {
+            println!(
+                "{:key_width$}  {:value_width$}  {:kind_width$}  {}",
+                setting.key, value, setting.kind.name(), setting.description,
+                key_width = key_width, value_width = value_width, kind_width = kind_width
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use anyhow::{Result, Context};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::branch::Branch;
+use crate::config::{expand_home, ProjectConfig};
+use crate::db::{DB, project::Project, running::Running, settings::Settings};
+use crate::process::{ProcessManager, attach::wait_foreground, stop::StopPolicy};
+
+#[derive(Debug)]
+pub struct ShellCommand {
+    pub alias: String,
+}
+
+impl Branch for ShellCommand {
+    fn execute(&self) -> Result<()> {
+        let project = Project::resolve(&self.alias)?;
+        let config = project.config()?;
+        let settings = config.shell.as_ref();
+
+        let program = match settings.and_then(|settings| settings.program.as_deref()) {
+            Some(name) => Some(config.program(Some(name))?),
+            None => config.program(None).ok(),
+        };
+
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+
+        let mut cmd = Command::new(&shell);
+        cmd.env("DEVINIT_PROJECT", &project.name);
+
+        if let Some(program) = program {
+            cmd.envs(program.environment()?);
+        }
+
+        if let Some(dir) = config.directory() {
+            cmd.current_dir(expand_home(dir));
+        }
+
+        if settings.and_then(|settings| settings.history).unwrap_or(false) {
+            // Shells only agree on HISTFILE, fish names its history files through fish_history
+            let name: String = project.name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+
+            let history = match Settings::get("history_dir")? {
+                Some(dir) => PathBuf::from(dir),
+                None => DB::data_dir()?.join("history"),
+            };
+            fs::create_dir_all(&history).context("Failed to create history directory.")?;
+
+            cmd.env("HISTFILE", history.join(&name))
+                .env("fish_history", format!("devinit_{}", name));
+        }
+
+        println!("Entering {}, exit the shell to leave.", project.name);
+
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to start {}.", shell))?;
+
+        let code = wait_foreground(&mut child)?;
+
+        if settings.and_then(|settings| settings.stop_on_exit).unwrap_or(false) {
+            stop_auto_close(&project, &config)?;
+        }
+
+        if code != 0 {
+            std::process::exit(code);
+        }
+
+        Ok(())
+    }
+}
+
+/// Stops the launched programs marked auto_close, leaving the rest running.
+fn stop_auto_close(project: &Project, config: &ProjectConfig) -> Result<()> {
+    let running = Running::get(project.id)?;
+    let mut exits = Vec::new();
+
+    for entry in running.iter().rev() {
+        let program = config.programs.list
+            .iter()
+            .find(|program| program.name == entry.name && program.auto_close == Some(true));
+
+        if let Some(program) = program {
+            let code = ProcessManager::stop(entry, &StopPolicy::new(Some(program))?)?;
+            entry.remove()?;
+            exits.push((entry.name.to_string(), code));
+        }
+    }
+
+    // on_exit hooks wait until nothing of the project is left running
+    if !exits.is_empty() && exits.len() == running.len() {
+        project.exited(&exits, 0)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+
+use crate::branch::Branch;
+use crate::cli::Shell;
+
+/// devinit cannot change the directory of the shell that started it, so `goto` asks for the
+/// project's directory, changes into it itself, then hands the launch back to devinit. Projects
+/// without a directory launch from wherever the shell is.
+const POSIX_GOTO: &str = r#"goto() {
+    if [ "$#" -eq 0 ]; then
+        command devinit
+        return
+    fi
+
+    local dir
+    dir="$(command devinit cd "$1")" || return
+    if [ -n "$dir" ]; then
+        cd "$dir" || return
+    fi
+    command devinit "$@"
+}
+"#;
+
+const FISH_GOTO: &str = r#"function goto
+    if test (count $argv) -eq 0
+        command devinit
+        return
+    end
+
+    set -l dir (command devinit cd $argv[1]); or return
+    if test -n "$dir"
+        cd $dir; or return
+    end
+    command devinit $argv
+end
+"#;
+
+#[derive(Debug)]
+pub struct ShellInitCommand {
+    pub shell: Shell,
+}
+
+impl Branch for ShellInitCommand {
+    fn execute(&self) -> Result<()> {
+        match self.shell {
+            Shell::Bash | Shell::Zsh => print!("{}", POSIX_GOTO),
+            Shell::Fish => print!("{}", FISH_GOTO),
+        }
+
+        Ok(())
+    }
+}
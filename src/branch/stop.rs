@@ -0,0 +1,64 @@
+use anyhow::{anyhow, Result};
+
+use crate::branch::Branch;
+use crate::db::{
+    project::Project,
+    running::Running
+};
+use crate::process::{ProcessManager, stop::StopPolicy};
+
+#[derive(Debug)]
+pub struct StopCommand {
+    pub alias: String,
+}
+
+impl Branch for StopCommand {
+    fn execute(&self) -> Result<()> {
+        let project = Project::resolve(&self.alias)?;
+
+        let running = Running::get(project.id)?;
+
+        if running.is_empty() {
+            println!("Nothing is running for project {}.", project.name);
+            return Ok(());
+        }
+
+        let config = project.config().ok();
+
+        let mut exits = Vec::new();
+        let mut failed = Vec::new();
+
+        // Undo launches in reverse so dependencies go down last. A failure leaves the
+        // program recorded, so it can still be stopped once the problem is fixed.
+        for entry in running.iter().rev() {
+            let program = config
+                .as_ref()
+                .and_then(|config| config.programs.list.iter().find(|program| program.name == entry.name));
+
+            match StopPolicy::new(program).and_then(|policy| ProcessManager::stop(entry, &policy)) {
+                Ok(code) => {
+                    entry.remove()?;
+                    exits.push((entry.name.to_string(), code));
+                }
+                Err(error) => {
+                    eprintln!("Failed to stop {}: {:#}", entry.name, error);
+                    failed.push(entry.name.as_str());
+                }
+            }
+        }
+
+        // The project's own on_exit hooks wait until nothing of it is left running
+        if !failed.is_empty() {
+            project.programs_exited(&exits)?;
+
+            return Err(anyhow!(
+                "{} of {} could not be stopped and {} still recorded as running.",
+                failed.join(", "),
+                project.name,
+                if failed.len() == 1 { "is" } else { "are" }
+            ));
+        }
+
+        project.exited(&exits, 0)
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Result};
+use dialoguer::{Confirm, Input};
+
+use crate::{TemplateAction, TemplateCommand};
+use crate::branch::Branch;
+use crate::config::Editor;
+use crate::format::project_format;
+use crate::db::{project::Project, template::Template};
+
+impl Branch for TemplateCommand {
+    fn execute(&self) -> Result<()> {
+        match &self.action {
+            TemplateAction::Add { name, from } => {
+                if Template::get(name).is_ok() {
+                    return Err(anyhow!("Template {} already exists, change it with `devinit template edit {}`.", name, name));
+                }
+
+                let start = match from {
+                    Some(project) => Project::resolve(project)?.toml,
+                    None => project_format()?,
+                };
+
+                let toml = Editor::input_editor(&start)?;
+
+                Template::add(name, toml)?;
+
+                println!("Successfully added template {}.", name);
+            }
+            TemplateAction::List => {
+                let templates = Template::get_all()?;
+
+                if templates.is_empty() {
+                    println!("No templates yet, add one with `devinit template add <name>`.");
+                }
+
+                for template in templates {
+                    let placeholders = template.placeholders();
+
+                    if placeholders.is_empty() {
+                        println!("{}", template.name);
+                    } else {
+                        println!("{}  ({})", template.name, placeholders.join(", "));
+                    }
+                }
+            }
+            TemplateAction::Edit { name } => {
+                let template = Template::get(name)?;
+
+                let toml = Editor::input_editor(&template.toml)?;
+
+                Template::replace_toml(name, toml)?;
+            }
+            TemplateAction::Rm { name } => {
+                Template::get(name)?;
+
+                let confirm = Confirm::new()
+                    .with_prompt(format!("Are you sure you want to delete template {}?", name))
+                    .interact()?;
+
+                if !confirm { return Ok(()) }
+
+                Template::remove(name)?;
+
+                println!("Successfully removed template {}", name);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Template {
+    /// Prompts for every placeholder, offering the project name for `{{name}}`, and renders the template.
+    pub fn fill(&self, project: &str) -> Result<String> {
+        let mut values = Vec::new();
+
+        for placeholder in self.placeholders() {
+            let mut input = Input::<String>::new().with_prompt(&placeholder);
+
+            if placeholder == "name" {
+                input = input.default(project.to_string());
+            }
+
+            values.push((placeholder, input.interact_text()?));
+        }
+
+        Ok(self.render(&values))
+    }
+}
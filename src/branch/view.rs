@@ -1,25 +1,36 @@
-use anyhow::Result;
+use anyhow::{Result, Context};
 
 use crate::db::{
     project::Project,
     alias::Alias
 };
 use crate::branch::Branch;
+use crate::picker::Picker;
 
 #[derive(Debug)]
 pub struct ViewCommand {
-    pub alias: String,
+    pub alias: Option<String>,
+    pub resolved: bool,
 }
 
 impl Branch for ViewCommand {
     fn execute(&self) -> Result<()> {
-        println!("Viewing details of {}", &self.alias);
+        let project = match &self.alias {
+            Some(alias) => Project::resolve(alias)?,
+            None => Picker::project("Project to view")?,
+        };
 
-        let project = Project::get(&[self.alias.to_string()])?;
+        println!("Viewing details of {}", &project.name);
 
         let aliases = Alias::get(project.id)?;
 
-        println!("Project Name: {}\nAliases: {}\nProject TOML: \n{}", project.name, aliases, project.toml, );
+        let toml = if self.resolved {
+            toml::to_string(&project.resolved()?).context("Failed to serialize the resolved configuration.")?
+        } else {
+            project.toml.to_string()
+        };
+
+        println!("Project Name: {}\nAliases: {}\nProject TOML: \n{}", project.name, aliases, toml, );
 
         Ok(())
     }
@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 /// The main CLI struct for the `devinit` application.
 #[derive(Parser, Debug)]
@@ -9,18 +9,27 @@ pub struct Cli {
     pub command: Option<Commands>,
 
     /// The project name to run
-    #[arg()]
+    #[arg(value_name = "PROJECT")]
     pub project_name: Option<String>,
+
+    /// Keep devinit in the foreground and stream every program's output.
+    #[arg(short, long, requires = "project_name")]
+    pub attach: bool,
+
+    /// Editor command to use for this run, like `code --wait`, instead of the editor setting.
+    #[arg(long, global = true, value_name = "COMMAND")]
+    pub editor: Option<String>,
 }
 
 /// Enum representing the subcommands of the `devinit` application.
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     Default {
-        alias: String,
+        alias: Option<String>,
     },
 
     /// Add a project or alias to the program.
+    #[command(visible_alias = "new")]
     Add(AddCommand),
 
     /// Remove a project or alias from the program.
@@ -28,20 +37,157 @@ pub enum Commands {
 
     /// View details of a project or its aliases.
     View {
-        alias: String,
+        /// The project name or alias to view, picked interactively when omitted.
+        #[arg(value_name = "PROJECT")]
+        alias: Option<String>,
+
+        /// Show the configuration with everything it extends merged in.
+        #[arg(long)]
+        resolved: bool,
     },
 
-    /// List all projects and their aliases.
+    /// List all projects and their aliases, most frequently and recently used first.
     List,
 
-    /// Set options like preferred editor (not expanded yet).
+    /// Show recent launches, of one project or of all of them.
+    History {
+        /// The project name or alias to show launches of.
+        #[arg(value_name = "PROJECT")]
+        alias: Option<String>,
+    },
+
+    /// Set a setting, see `devinit settings list` for what there is.
     Set (SetCommand),
 
+    /// Print the value of a setting.
+    Get {
+        /// The setting to print.
+        #[arg(value_name = "SETTING")]
+        key: String,
+    },
+
+    /// Reset a setting to its default.
+    Unset {
+        /// The setting to reset.
+        #[arg(value_name = "SETTING")]
+        key: String,
+    },
+
+    /// Show the settings.
+    Settings(SettingsCommand),
+
     /// Edit a project or alias.
     Edit {
-        /// The project name or alias to edit.
+        /// The project name or alias to edit, picked interactively when omitted.
+        #[arg(value_name = "PROJECT")]
+        alias: Option<String>,
+    },
+
+    /// List the earlier configurations of a project.
+    HistoryConfig {
+        /// The project name or alias to list revisions of.
+        #[arg(value_name = "PROJECT")]
         alias: String,
     },
+
+    /// Show how a project's configuration changed since a revision.
+    Diff {
+        /// The project name or alias to diff.
+        #[arg(value_name = "PROJECT")]
+        alias: String,
+
+        /// The revision to compare against, the latest when omitted.
+        #[arg(value_name = "REV")]
+        rev: Option<u32>,
+    },
+
+    /// Restore a project's configuration from a revision.
+    Revert {
+        /// The project name or alias to revert.
+        #[arg(value_name = "PROJECT")]
+        alias: String,
+
+        /// The revision to restore, see `devinit history-config`.
+        #[arg(value_name = "REV")]
+        rev: u32,
+    },
+
+    /// Manage the templates new projects can start from.
+    Template(TemplateCommand),
+
+    /// Import projects exported by devinit, or a project from a Procfile, tmuxinator or tmuxp config.
+    Import(ImportCommand),
+
+    /// Export projects with their aliases and launch history, to back them up or keep them in version control.
+    Export(ExportCommand),
+
+    /// Import the config.toml and projects/*.toml layout of older devinit versions.
+    MigrateLegacy(MigrateLegacyCommand),
+
+    /// Run a project in the foreground, streaming every program's output.
+    Run {
+        /// The project name or alias to run.
+        #[arg(value_name = "PROJECT")]
+        alias: String,
+    },
+
+    /// Run a one-off command with a program's working directory and environment.
+    Exec(ExecCommand),
+
+    /// Stop the programs and compose services a project launched.
+    #[command(visible_alias = "down")]
+    Stop {
+        /// The project name or alias to stop.
+        #[arg(value_name = "PROJECT")]
+        alias: String,
+    },
+
+    /// Print the JSON Schema of project configurations.
+    Schema {
+        /// Print a .taplo.toml rule applying the schema to the files devinit edits instead.
+        #[arg(long)]
+        taplo: bool,
+    },
+
+    /// Print the directory of a project, for shell integration.
+    Cd {
+        /// The project name or alias to print the directory of.
+        #[arg(value_name = "PROJECT")]
+        alias: String,
+    },
+
+    /// Open a subshell in a project's directory with its environment applied.
+    Shell {
+        /// The project name or alias to enter.
+        #[arg(value_name = "PROJECT")]
+        alias: String,
+    },
+
+    /// Print a `goto` function that changes into a project's directory and launches it.
+    ShellInit {
+        /// The shell to integrate with.
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// Print a completion script for bash, zsh or fish.
+    Completions {
+        /// The shell to complete for.
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// Print the candidates for the word being completed, used by the completion scripts.
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// The shell asking, which decides whether subcommands are listed too.
+        #[arg(value_enum)]
+        shell: Shell,
+
+        /// The command line up to and including the word being completed.
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
 }
 
 /// Subcommand for `add` operations.
@@ -58,26 +204,188 @@ pub struct AddCommand {
     /// Implicit project name or alias (positional argument).
     #[arg(conflicts_with_all = &["project", "alias"])]
     pub proj_name: Option<String>,
+
+    /// Start the project from a template instead of the blank format.
+    #[arg(short, long, value_name = "TEMPLATE", conflicts_with = "alias")]
+    pub template: Option<String>,
 }
 
 /// Subcommand for `remove` operations.
 #[derive(Parser, Debug)]
 pub struct RemoveCommand {
     /// Explicitly specify the project name to remove.
-    #[arg(long, conflicts_with = "alias")]
+    #[arg(long, value_name = "PROJECT", conflicts_with = "alias")]
     pub project: Option<String>,
 
     /// Explicitly specify the alias to remove.
-    #[arg(long, conflicts_with = "project")]
+    #[arg(long, value_name = "ALIAS", conflicts_with = "project")]
     pub alias: Option<String>,
 
     /// Implicit project name or alias (positional argument).
-    #[arg(conflicts_with_all = &["project", "alias"])]
+    #[arg(value_name = "PROJECT", conflicts_with_all = &["project", "alias"])]
     pub proj_name: Option<String>,
 }
 
 #[derive(Parser, Debug)]
 pub struct SetCommand {
+    /// The setting to change.
+    #[arg(value_name = "SETTING")]
     pub option: Option<String>,
+
+    /// The new value, flips a bool setting when omitted.
     pub value: Option<String>
 }
+
+/// Subcommand for `settings` operations.
+#[derive(Parser, Debug)]
+pub struct SettingsCommand {
+    #[command(subcommand)]
+    pub action: SettingsAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SettingsAction {
+    /// List every setting with its value, type and description.
+    List,
+}
+
+/// Subcommand for `template` operations.
+#[derive(Parser, Debug)]
+pub struct TemplateCommand {
+    #[command(subcommand)]
+    pub action: TemplateAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TemplateAction {
+    /// Write a new template in the editor.
+    Add {
+        /// The name to use the template by.
+        name: String,
+
+        /// Start from an existing project's configuration instead of the blank format.
+        #[arg(long, value_name = "PROJECT")]
+        from: Option<String>,
+    },
+
+    /// List all templates.
+    List,
+
+    /// Edit a template.
+    Edit {
+        /// The template to edit.
+        #[arg(value_name = "TEMPLATE")]
+        name: String,
+    },
+
+    /// Remove a template.
+    #[command(visible_alias = "remove")]
+    Rm {
+        /// The template to remove.
+        #[arg(value_name = "TEMPLATE")]
+        name: String,
+    },
+}
+
+/// Subcommand for `import` operations.
+#[derive(Parser, Debug)]
+pub struct ImportCommand {
+    /// The format of the file to import, a devinit export when omitted.
+    #[arg(long, value_enum)]
+    pub from: Option<ImportFormat>,
+
+    /// The file to import, or a directory written by `devinit export --dir`.
+    pub file: String,
+
+    /// The project name, defaults to the name in the file or the file name.
+    #[arg(long, requires = "from")]
+    pub name: Option<String>,
+
+    /// Aliases for the project, separated by commas.
+    #[arg(long, value_delimiter = ',', requires = "from")]
+    pub alias: Vec<String>,
+
+    /// What to do with exported projects whose name is already taken.
+    #[arg(long, value_enum, default_value_t = Conflict::Skip, conflicts_with = "from")]
+    pub conflict: Conflict,
+
+    /// Import without asking for confirmation after the preview.
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// Subcommand for `export` operations.
+#[derive(Parser, Debug)]
+pub struct ExportCommand {
+    /// Write one file per project into this directory.
+    #[arg(long, value_name = "PATH", conflicts_with = "bundle")]
+    pub dir: Option<String>,
+
+    /// Write every project into this file.
+    #[arg(long, value_name = "FILE")]
+    pub bundle: Option<String>,
+
+    /// The projects to export, all of them when none are given. Without --dir or --bundle
+    /// the export is printed.
+    #[arg(value_name = "PROJECT")]
+    pub projects: Vec<String>,
+}
+
+/// Subcommand for `migrate-legacy` operations.
+#[derive(Parser, Debug)]
+pub struct MigrateLegacyCommand {
+    /// The legacy config directory, defaults to ~/.config/development_initializer.
+    #[arg(long, value_name = "PATH")]
+    pub dir: Option<String>,
+
+    /// What to do with legacy projects whose name is already taken.
+    #[arg(long, value_enum, default_value_t = Conflict::Skip)]
+    pub conflict: Conflict,
+
+    /// Migrate without asking for confirmation after the preview.
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// How `import` treats a project whose name is already taken.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Conflict {
+    /// Keep the existing project.
+    Skip,
+    /// Replace the existing project's configuration.
+    Overwrite,
+    /// Import under a free name like web-2.
+    Rename,
+}
+
+/// Subcommand for `exec` operations.
+#[derive(Parser, Debug)]
+pub struct ExecCommand {
+    /// The project name or alias to run the command for.
+    #[arg(value_name = "PROJECT")]
+    pub alias: String,
+
+    /// The program whose working directory and environment to use, defaults to the first process.
+    #[arg(short, long)]
+    pub program: Option<String>,
+
+    /// The command to run and its arguments, after `--`.
+    #[arg(last = true, required = true)]
+    pub command: Vec<String>,
+}
+
+/// Configuration formats `import` understands.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ImportFormat {
+    Procfile,
+    Tmuxinator,
+    Tmuxp,
+}
+
+/// Shells devinit can generate completions and integration for.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
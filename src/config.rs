@@ -1,56 +1,629 @@
 use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use schemars::{JsonSchema, Schema, generate::SchemaSettings};
 use serde::{Deserialize, Serialize};
-use anyhow::{anyhow, Result};
-
-use crate::db::settings::Settings;
-
-pub const PROJECT_FORMAT: &str = "
-[programs]
-    [[programs.list]]
-    name = \"\"
-    path = \"\"
-    working_directory = \"\"
-    args = []
-    commands = []
-    output_mode = []
-    env = []
-    auto_close = false
-    set_active_window = false
-";
-
-#[derive(Debug, Serialize, Deserialize)]
+use anyhow::{anyhow, Result, Context};
+use toml::Value;
+
+use crate::db::{DB, project::Project, settings::Settings};
+use crate::matching::suggestions;
+
+/// A program devinit launches, either a process or a set of compose services.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Program {
+    /// Name the program is shown and matched by, unique within the project.
     pub name: String,
+    /// Executable to run, looked up in PATH unless it contains a slash.
+    #[serde(default)]
     pub path: String,
+    /// How the program is launched: "process" (the default) or "compose".
+    pub kind: Option<String>,
+    /// Directory the program runs in, a leading `~` stands for the home directory.
     pub working_directory: Option<String>,
+    /// Arguments passed to the executable.
     pub args: Option<Vec<String>>,
+    /// Lines written to the program's stdin once it started, e.g. for a shell.
     pub commands: Option<Vec<String>>,
-    pub output_mode: Option<String>, // e.g., "null", "inherit", "log"
+    /// Where the output of a detached launch goes: "inherit" (the terminal) or "null" (discarded).
+    pub output_mode: Option<String>,
+    /// Environment variables, `${VAR}` and `${VAR:-default}` are interpolated from env_file and devinit's environment.
     pub env: Option<HashMap<String, String>>,
+    /// Dotenv file of KEY=VALUE lines loaded before env, relative to working_directory.
+    pub env_file: Option<String>,
+    /// Stop the program when leaving `devinit shell` with stop_on_exit set.
     pub auto_close: Option<bool>,
+    /// Focus the program's window once launched, not implemented yet.
     pub set_active_window: Option<bool>,
+    /// Overrides of the global settings for this program.
     pub settings: Option<Settings>,
+    /// Compose file to use with kind = "compose", the compose default when unset.
+    pub compose_file: Option<String>,
+    /// Compose services to start, all of them when unset.
+    pub services: Option<Vec<String>>,
+    /// Compose project name, passed as `docker compose -p`.
+    pub project_name: Option<String>,
+    /// TCP ports the program listens on, checked for conflicts before launching.
+    pub ports: Option<Vec<u16>>,
+    /// Shell commands run around this program's launch.
+    pub hooks: Option<Hooks>,
+    /// Signal sent to stop the program, like "SIGINT" or "TERM", SIGTERM when unset.
+    pub stop_signal: Option<String>,
+    /// Time to wait for the program to stop before SIGKILL, like "500ms", "10s" or "2m".
+    pub stop_timeout: Option<String>,
+    /// Scheduling priority and resource limits.
+    pub limits: Option<Limits>,
+    /// Drop the program of this name inherited through extends.
+    pub remove: Option<bool>,
+    /// Drop inherited keys of this program, like "env.DEBUG" or "hooks.on_exit".
+    pub unset: Option<Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Scheduling priority and resource limits applied to a program before it executes.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Limits {
+    /// Niceness from -20 (highest priority) to 19 (lowest).
+    pub nice: Option<i32>,
+    /// I/O scheduling class: "idle", "best-effort" or "realtime".
+    pub ionice: Option<String>,
+    /// I/O priority within best-effort or realtime, from 0 (highest) to 7 (lowest).
+    pub ionice_level: Option<u8>,
+    /// Maximum number of open files (RLIMIT_NOFILE).
+    pub nofile: Option<u64>,
+    /// Maximum virtual memory in bytes (RLIMIT_AS).
+    pub address_space: Option<u64>,
+    /// Maximum CPU time in seconds (RLIMIT_CPU).
+    pub cpu_time: Option<u64>,
+    /// CPUs the program may run on, numbered from 0.
+    pub cpu_affinity: Option<Vec<usize>>,
+}
+
+/// Shell commands run around a launch, at the global, project or program level.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    /// Runs before launching, a failure aborts the launch.
+    pub pre_launch: Option<String>,
+    /// Runs once everything launched.
+    pub post_launch: Option<String>,
+    /// Runs when the launch failed.
+    pub on_failure: Option<String>,
+    /// Runs once the programs exited or were stopped, with DEVINIT_EXIT_CODE set.
+    pub on_exit: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Programs {
+    /// The programs to launch, in order.
     pub list: Vec<Program>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ProjectConfig {
+    /// Name of a project whose configuration this one builds on.
+    pub extends: Option<String>,
+    /// Drop inherited keys of the project, like "root" or "hooks.pre_launch".
+    pub unset: Option<Vec<String>>,
+    /// Directory of the project, printed by `devinit cd` and entered by `goto` and `devinit shell`.
+    pub root: Option<String>,
+    /// The programs of the project.
     pub programs: Programs,
+    /// Shell commands run around launching the whole project.
+    pub hooks: Option<Hooks>,
+    /// How `devinit shell` sets up the project's subshell.
+    pub shell: Option<ShellConfig>,
+}
+
+/// How `devinit shell` sets up the subshell of a project.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ShellConfig {
+    /// Program whose environment the shell gets, the first process when unset.
+    pub program: Option<String>,
+    /// Keep a separate shell history for the project.
+    pub history: Option<bool>,
+    /// Stop the auto_close programs when the shell exits.
+    pub stop_on_exit: Option<bool>,
+}
+
+impl ProjectConfig {
+    /// Where the project lives: its root, or else the first program's working directory.
+    pub fn directory(&self) -> Option<&str> {
+        let directories = std::iter::once(&self.root)
+            .chain(self.programs.list.iter().map(|program| &program.working_directory));
+
+        directories
+            .flatten()
+            .map(String::as_str)
+            .find(|directory| !directory.is_empty())
+    }
+
+    /// The program named `name`, or the first process when no name is given.
+    pub fn program(&self, name: Option<&str>) -> Result<&Program> {
+        let mut programs = self.programs.list.iter();
+
+        match name {
+            Some(name) => programs
+                .find(|program| program.name == name)
+                .ok_or_else(|| anyhow!("There is no program named {}.", name)),
+            None => programs
+                .find(|program| matches!(program.kind.as_deref(), None | Some("process")))
+                .ok_or_else(|| anyhow!("There is no process to take the environment from.")),
+        }
+    }
 }
 
 pub struct Editor {}
 
+/// The editor given with `--editor`, which beats the editor setting for this run.
+static EDITOR_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// GUI editors return right away unless told to wait, which leaves nothing to read back.
+/// The first flag is the one added, the others are spellings that already wait.
+const WAIT_FLAGS: &[(&[&str], &[&str])] = &[
+    (&["code", "code-insiders", "codium", "cursor", "windsurf"], &["--wait", "-w"]),
+    (&["subl", "sublime_text"], &["--wait", "-w"]),
+    (&["zed", "zeditor"], &["--wait", "-w"]),
+    (&["gedit", "gnome-text-editor"], &["--wait", "-w"]),
+    (&["kate"], &["--block", "-b"]),
+    (&["mate"], &["-w", "--wait"]),
+    (&["gvim", "mvim"], &["--nofork", "-f"]),
+];
+
 impl Editor {
+    pub fn override_with(command: String) {
+        let _ = EDITOR_OVERRIDE.set(command);
+    }
+
     pub fn input_editor(format: &str) -> Result<String> {
-        let input = match edit::edit(format) {
-            Ok(i) => i,
-            Err(e) => return Err(anyhow!(e))
+        // A .toml name lets editors pick the right syntax and language server
+        let mut file = edit::Builder::new()
+            .prefix("devinit-")
+            .suffix(".toml")
+            .tempfile()
+            .context("Failed to create a file to edit.")?;
+
+        file.write_all(format.as_bytes())
+            .context("Failed to write the file to edit.")?;
+
+        let command = Self::command()?;
+        let status = Command::new(&command[0])
+            .args(&command[1..])
+            .arg(file.path())
+            .status()
+            .with_context(|| format!("Failed to start editor {}.", command[0]))?;
+
+        if !status.success() {
+            return Err(anyhow!("Editor {} exited with {}.", command[0], status));
+        }
+
+        // Read back by path, some editors save by replacing the file
+        fs::read_to_string(file.path()).context("Failed to read the edited file.")
+    }
+
+    /// The editor command split into words, from `--editor`, the editor setting, $VISUAL,
+    /// $EDITOR and finally whatever editor can be found.
+    fn command() -> Result<Vec<String>> {
+        let env = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+
+        let configured = match EDITOR_OVERRIDE.get() {
+            Some(command) => Some(command.clone()),
+            None => Settings::get("editor")?.or_else(|| env("VISUAL")).or_else(|| env("EDITOR")),
+        };
+
+        let command: Vec<String> = match configured {
+            Some(command) => Self::words(&command)?,
+            None => vec![
+                edit::get_editor()
+                    .context("No editor found, set one with `devinit set editor <command>`.")?
+                    .to_string_lossy()
+                    .into_owned(),
+            ],
         };
 
-        Ok(input)
+        if command.is_empty() {
+            return Err(anyhow!("The editor command is empty."));
+        }
+
+        Ok(Self::with_wait_flag(command))
+    }
+
+    /// Splits an editor command the way a shell would, so paths with spaces can be quoted.
+    pub fn words(command: &str) -> Result<Vec<String>> {
+        let words = shell_words::split(command)
+            .with_context(|| format!("Failed to split the editor command {}.", command))?;
+
+        Ok(words.iter().map(|word| expand_home(word)).collect())
+    }
+
+    /// Adds the flag that makes a GUI editor wait, unless the command already has one.
+    fn with_wait_flag(mut command: Vec<String>) -> Vec<String> {
+        let program = Path::new(&command[0])
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        if let Some((_, flags)) = WAIT_FLAGS.iter().find(|(names, _)| names.contains(&program.as_str()))
+            && !command[1..].iter().any(|arg| flags.contains(&arg.as_str()))
+        {
+            command.insert(1, flags[0].to_string());
+        }
+
+        command
+    }
+
+    /// Opens the editor until the text is a valid configuration for the project named `name`.
+    /// Errors are reported in a comment header on top of the text, and saving it unchanged or
+    /// emptying it aborts.
+    pub fn input_config(name: &str, format: &str) -> Result<String> {
+        // Taplo based editors validate and complete against the schema this points to
+        let directive = format!("{}file://{}\n", SCHEMA_DIRECTIVE, install_schema()?.display());
+
+        let mut shown = format.to_string();
+        let mut previous: Option<String> = None;
+
+        loop {
+            let input = Self::input_editor(&format!("{}{}", directive, shown))?;
+            let input = strip_error_header(&input);
+
+            if input.trim().is_empty() || previous.as_deref() == Some(input.as_str()) {
+                return Err(anyhow!("Aborted, the configuration of {} was not saved.", name));
+            }
+
+            match check(name, &input) {
+                Ok(_) => return Ok(input),
+                Err(error) => {
+                    shown = format!("{}{}", error_header(&error, &input), input);
+                    previous = Some(input);
+                }
+            }
+        }
+    }
+}
+
+const ERROR_HEADER: &str = "# devinit: ";
+const SCHEMA_DIRECTIVE: &str = "#:schema ";
+
+/// Expands a leading `~` to the home directory, as paths in configurations and settings commonly start with it.
+pub fn expand_home(path: &str) -> String {
+    match (path.strip_prefix('~'), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) if rest.is_empty() || rest.starts_with('/') => format!("{}{}", home, rest),
+        _ => path.to_string(),
+    }
+}
+
+/// JSON Schema of project configurations, as draft-07 for the widest editor support.
+pub fn schema() -> Schema {
+    SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<ProjectConfig>()
+}
+
+/// Writes the schema to the data directory for editors to load, returning its path.
+pub fn install_schema() -> Result<PathBuf> {
+    let path = DB::data_dir()?.join("project.schema.json");
+    let schema = serde_json::to_string_pretty(&schema()).context("Failed to serialize the schema.")?;
+
+    if fs::read_to_string(&path).ok().as_deref() != Some(schema.as_str()) {
+        fs::write(&path, schema).context("Failed to write the schema.")?;
+    }
+
+    Ok(path)
+}
+
+/// A "did you mean" hint for the unknown field errors of strict parsing.
+pub fn did_you_mean(message: &str) -> Option<String> {
+    let rest = message.strip_prefix("unknown field `")?;
+    let (field, rest) = rest.split_once('`')?;
+
+    // The expected fields follow in backticks, like "expected one of `name`, `path`"
+    let expected = rest.split('`').skip(1).step_by(2);
+
+    suggestions(field, expected)
+        .first()
+        .map(|suggestion| format!("Did you mean `{}`?", suggestion))
+}
+
+/// Checks a configuration before it is stored. Parsing it directly keeps the position of errors,
+/// one that extends another project is checked merged.
+fn check(name: &str, toml: &str) -> Result<()> {
+    let value: Value = toml::from_str(toml)?;
+
+    match value.get("extends") {
+        None => toml::from_str::<ProjectConfig>(toml).map(|_| ()).map_err(Into::into),
+        Some(_) => Project { id: 0, name: name.to_string(), toml: toml.to_string() }.config().map(|_| ()),
+    }
+}
+
+/// Drops the leading error and schema comments devinit added, keeping the rest byte for byte.
+fn strip_error_header(text: &str) -> String {
+    let mut rest = text;
+
+    while rest.starts_with(ERROR_HEADER) || rest.starts_with(SCHEMA_DIRECTIVE) {
+        rest = rest.split_once('\n').map_or("", |(_, after)| after);
+    }
+
+    rest.to_string()
+}
+
+/// Comment lines explaining why the configuration was not saved, with the position of the
+/// error in the reopened file when TOML knows it.
+fn error_header(error: &anyhow::Error, text: &str) -> String {
+    let toml_error = error.downcast_ref::<toml::de::Error>();
+
+    let mut message = match toml_error {
+        Some(toml_error) => toml_error.message().to_string(),
+        None => error.chain().map(|cause| cause.to_string()).collect::<Vec<_>>().join("\n"),
+    };
+
+    if let Some(hint) = toml_error.and_then(|toml_error| did_you_mean(toml_error.message())) {
+        message = format!("{}\n{}", message, hint);
+    }
+
+    let mut lines = vec!["The configuration was not saved. Fix it, or save it unchanged or empty to abort.".to_string()];
+    let message_lines: Vec<&str> = message.lines().collect();
+
+    let position = toml_error
+        .and_then(|toml_error| toml_error.span())
+        .map(|span| {
+            let before = &text[..span.start.min(text.len())];
+            let line = before.matches('\n').count() + 1;
+            let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+
+            // The schema directive and the header push the error down in the reopened file
+            (line + message_lines.len() + 3, column)
+        });
+
+    match position {
+        Some((line, column)) => lines.push(format!("Error at line {}, column {}:", line, column)),
+        None => lines.push("Error:".to_string()),
+    }
+
+    lines.extend(message_lines.iter().map(|line| format!("  {}", line)));
+
+    lines
+        .iter()
+        .map(|line| format!("{}{}\n", ERROR_HEADER, line))
+        .collect()
+}
+
+/// Deep-merges a project's TOML onto the one it extends. Tables merge key by key, programs
+/// merge by name unless marked `remove = true`, and any other value replaces the inherited one.
+/// The project and each program can drop inherited keys by listing their paths in `unset`.
+pub fn merge(base: Value, overlay: Value) -> Value {
+    merge_at(base, overlay, "")
+}
+
+fn merge_at(base: Value, overlay: Value, path: &str) -> Value {
+    match (base, overlay) {
+        (Value::Table(mut base), Value::Table(mut overlay)) => {
+            // Projects and programs both merge at the top, where unset is allowed
+            let unset = if path.is_empty() { overlay.remove("unset") } else { None };
+
+            for key in unset.iter().flat_map(|unset| unset.as_array()).flatten().filter_map(Value::as_str) {
+                unset_path(&mut base, key);
+            }
+
+            for (key, value) in overlay {
+                let path = if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+
+                let merged = match base.remove(&key) {
+                    Some(inherited) => merge_at(inherited, value, &path),
+                    None => value,
+                };
+
+                base.insert(key, merged);
+            }
+
+            Value::Table(base)
+        }
+        (Value::Array(base), Value::Array(overlay)) if path == "programs.list" => {
+            Value::Array(merge_programs(base, overlay))
+        }
+        (_, overlay) => overlay,
+    }
+}
+
+/// Removes the value at a dotted path like "env.DEBUG", if there is one.
+fn unset_path(table: &mut toml::Table, path: &str) {
+    match path.split_once('.') {
+        Some((key, rest)) => {
+            if let Some(Value::Table(inner)) = table.get_mut(key) {
+                unset_path(inner, rest);
+            }
+        }
+        None => {
+            table.remove(path);
+        }
+    }
+}
+
+fn merge_programs(mut programs: Vec<Value>, overlay: Vec<Value>) -> Vec<Value> {
+    let name = |program: &Value| program.get("name").and_then(Value::as_str).map(String::from);
+
+    for program in overlay {
+        let position = name(&program)
+            .and_then(|overlay_name| programs.iter().position(|inherited| name(inherited).as_ref() == Some(&overlay_name)));
+        let remove = program.get("remove").and_then(Value::as_bool).unwrap_or(false);
+
+        match (position, remove) {
+            (Some(index), true) => {
+                programs.remove(index);
+            }
+            (None, true) => (),
+            (Some(index), false) => {
+                let inherited = programs.remove(index);
+                programs.insert(index, merge_at(inherited, program, ""));
+            }
+            (None, false) => programs.push(program),
+        }
+    }
+
+    programs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merged(base: &str, overlay: &str) -> Value {
+        merge(toml::from_str(base).unwrap(), toml::from_str(overlay).unwrap())
     }
-}
\ No newline at end of file
+
+    fn strings(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn splits_editor_commands_like_a_shell() {
+        assert_eq!(Editor::words("code --wait").unwrap(), ["code", "--wait"]);
+        assert_eq!(
+            Editor::words("'/opt/My Editor/edit' -n \"two words\"").unwrap(),
+            ["/opt/My Editor/edit", "-n", "two words"]
+        );
+        assert!(Editor::words("'unclosed").is_err());
+    }
+
+    #[test]
+    fn makes_gui_editors_wait() {
+        assert_eq!(Editor::with_wait_flag(strings(&["code"])), ["code", "--wait"]);
+        assert_eq!(Editor::with_wait_flag(strings(&["/usr/bin/kate", "-n"])), ["/usr/bin/kate", "--block", "-n"]);
+        assert_eq!(Editor::with_wait_flag(strings(&["subl", "-w"])), ["subl", "-w"]);
+        assert_eq!(Editor::with_wait_flag(strings(&["gvim", "--nofork"])), ["gvim", "--nofork"]);
+        assert_eq!(Editor::with_wait_flag(strings(&["nvim"])), ["nvim"]);
+    }
+
+    #[test]
+    fn strips_only_the_added_header() {
+        let text = "name = \"web\"\n\n# devinit: kept, not leading\nroot = \"~/web\"";
+
+        assert_eq!(strip_error_header(text), text);
+        assert_eq!(strip_error_header(&format!("{}file:///schema.json\n{}", SCHEMA_DIRECTIVE, text)), text);
+        assert_eq!(strip_error_header(&format!("{}error\n{}hint\n{}", ERROR_HEADER, ERROR_HEADER, text)), text);
+        assert_eq!(strip_error_header(&format!("{}error", ERROR_HEADER)), "");
+    }
+
+    #[test]
+    fn overrides_values_and_merges_tables() {
+        let merged = merged(
+            r#"
+            root = "/srv/base"
+            [hooks]
+            pre_launch = "git pull"
+            on_exit = "echo bye"
+            "#,
+            r#"
+            root = "/srv/web"
+            [hooks]
+            on_exit = "echo done"
+            "#,
+        );
+
+        assert_eq!(merged["root"].as_str(), Some("/srv/web"));
+        assert_eq!(merged["hooks"]["pre_launch"].as_str(), Some("git pull"));
+        assert_eq!(merged["hooks"]["on_exit"].as_str(), Some("echo done"));
+    }
+
+    #[test]
+    fn merges_programs_by_name() {
+        let merged = merged(
+            r#"
+            [[programs.list]]
+            name = "server"
+            path = "cargo"
+            args = ["run"]
+            env = { PORT = "8080", DEBUG = "1" }
+
+            [[programs.list]]
+            name = "browser"
+            path = "firefox"
+            "#,
+            r#"
+            [[programs.list]]
+            name = "server"
+            args = ["run", "--release"]
+            env = { PORT = "9090" }
+
+            [[programs.list]]
+            name = "worker"
+            path = "worker"
+            "#,
+        );
+
+        let programs = merged["programs"]["list"].as_array().unwrap();
+        let names: Vec<&str> = programs.iter().map(|program| program["name"].as_str().unwrap()).collect();
+
+        assert_eq!(names, ["server", "browser", "worker"]);
+        assert_eq!(programs[0]["path"].as_str(), Some("cargo"));
+        assert_eq!(programs[0]["args"].as_array().unwrap().len(), 2);
+        assert_eq!(programs[0]["env"]["PORT"].as_str(), Some("9090"));
+        assert_eq!(programs[0]["env"]["DEBUG"].as_str(), Some("1"));
+    }
+
+    #[test]
+    fn removes_programs_and_unsets_keys() {
+        let merged = merged(
+            r#"
+            root = "/srv/base"
+            [hooks]
+            pre_launch = "git pull"
+            on_exit = "echo bye"
+
+            [[programs.list]]
+            name = "server"
+            path = "cargo"
+            env = { PORT = "8080", DEBUG = "1" }
+            hooks = { on_exit = "echo server" }
+
+            [[programs.list]]
+            name = "browser"
+            path = "firefox"
+            "#,
+            r#"
+            unset = ["root", "hooks.pre_launch", "missing.key"]
+
+            [[programs.list]]
+            name = "server"
+            unset = ["env.DEBUG", "hooks"]
+
+            [[programs.list]]
+            name = "browser"
+            remove = true
+
+            [[programs.list]]
+            name = "never-inherited"
+            remove = true
+            "#,
+        );
+
+        assert!(merged.get("root").is_none());
+        assert!(merged.get("unset").is_none());
+        assert!(merged["hooks"].get("pre_launch").is_none());
+        assert_eq!(merged["hooks"]["on_exit"].as_str(), Some("echo bye"));
+
+        let programs = merged["programs"]["list"].as_array().unwrap();
+        assert_eq!(programs.len(), 1);
+        assert_eq!(programs[0]["env"]["PORT"].as_str(), Some("8080"));
+        assert!(programs[0]["env"].get("DEBUG").is_none());
+        assert!(programs[0].get("hooks").is_none());
+        assert!(programs[0].get("unset").is_none());
+    }
+
+    #[test]
+    fn unset_keys_can_be_set_again() {
+        let merged = merged(
+            "[hooks]\npre_launch = \"git pull\"",
+            "unset = [\"hooks\"]\n[hooks]\non_exit = \"echo bye\"",
+        );
+
+        assert!(merged["hooks"].get("pre_launch").is_none());
+        assert_eq!(merged["hooks"]["on_exit"].as_str(), Some("echo bye"));
+    }
+}
@@ -1,4 +1,4 @@
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use anyhow::{Result, Context};
 use crate::db::DB;
 
@@ -65,21 +65,19 @@ impl Alias {
     }
 
     pub fn add_all(id: i32, aliases: Vec<String>) -> Result<()> {
-        let mut conn = DB::connect()?;
-
-        let tx = conn.transaction()?;
+        DB::transaction(|conn| Self::add_all_in(conn, id, aliases))
+    }
 
+    /// Adds the aliases as part of the changes made on `conn`.
+    pub fn add_all_in(conn: &Connection, id: i32, aliases: Vec<String>) -> Result<()> {
         for alias in aliases {
-            tx.execute(
+            conn.execute(
             "INSERT INTO alias (id, alias) 
                 VALUES(?, ?) ", 
                 params![id, alias]
             )?;
         }
 
-        tx
-        .commit()?;
-
         Ok(())
     }
 
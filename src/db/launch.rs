@@ -0,0 +1,166 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::db::DB;
+
+/// A recorded `devinit <project>` invocation.
+pub struct Launch {
+    pub project: String,
+    pub alias: String,
+    pub time: String,
+    pub duration: i64,
+    pub success: bool,
+}
+
+/// A launch as stored, to carry a project's frecency over in an export.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Recorded {
+    pub alias: String,
+    /// Unix time of the launch.
+    pub timestamp: i64,
+    /// Milliseconds the launch took.
+    pub duration: i64,
+    pub success: bool,
+}
+
+/// How often and how recently a project was launched.
+#[derive(Default, Clone, Copy)]
+pub struct Usage {
+    pub score: i64,
+    pub count: i64,
+    pub last: i64,
+}
+
+impl Launch {
+    pub fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs() as i64)
+            .unwrap_or_default()
+    }
+
+    pub fn add(id: i32, alias: &str, duration: Duration, success: bool) -> Result<()> {
+        let conn = DB::connect()?;
+
+        conn.execute(
+            "INSERT INTO launches (id, alias, timestamp, duration, success)
+                VALUES(?, ?, ?, ?, ?)",
+            params![id, alias, Self::now(), duration.as_millis() as i64, success]
+        )
+        .context("Failed to record launch.")?;
+
+        Ok(())
+    }
+
+    /// Every launch as stored, oldest first, by project.
+    pub fn recorded() -> Result<HashMap<i32, Vec<Recorded>>> {
+        let conn = DB::connect()?;
+
+        let mut stmt = conn
+            .prepare("SELECT id, alias, timestamp, duration, success FROM launches ORDER BY timestamp, rowid")
+            .context("Failed to prepare launch export query.")?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get(0)?, Recorded { alias: row.get(1)?, timestamp: row.get(2)?, duration: row.get(3)?, success: row.get(4)? }))
+            })?
+            .collect::<Result<Vec<(i32, Recorded)>, _>>()
+            .context("Failed to execute launch export query.")?;
+
+        let mut result: HashMap<i32, Vec<Recorded>> = HashMap::new();
+        for (id, launch) in rows {
+            result.entry(id).or_default().push(launch);
+        }
+
+        Ok(result)
+    }
+
+    /// Adds launches recorded elsewhere as part of the changes made on `conn`.
+    pub fn add_recorded_in(conn: &Connection, id: i32, launches: &[Recorded]) -> Result<()> {
+        for launch in launches {
+            conn.execute(
+                "INSERT INTO launches (id, alias, timestamp, duration, success)
+                    VALUES(?, ?, ?, ?, ?)",
+                params![id, launch.alias, launch.timestamp, launch.duration, launch.success]
+            )
+            .context("Failed to record launch.")?;
+        }
+
+        Ok(())
+    }
+
+    /// Most recent launches first, of one project or of all of them.
+    pub fn history(id: Option<i32>, limit: u32) -> Result<Vec<Launch>> {
+        let conn = DB::connect()?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT p.name, l.alias, datetime(l.timestamp, 'unixepoch', 'localtime'), l.duration, l.success
+                FROM launches l
+                JOIN projects p ON p.id = l.id
+                WHERE ?1 IS NULL OR l.id = ?1
+                ORDER BY l.timestamp DESC, l.rowid DESC
+                LIMIT ?2")
+            .context("Failed to prepare launch history query.")?;
+
+        let result: Vec<Launch> = stmt
+            .query_map(params![id, limit], |row| {
+                Ok(
+                    Launch {
+                        project: row.get(0)?,
+                        alias: row.get(1)?,
+                        time: row.get(2)?,
+                        duration: row.get(3)?,
+                        success: row.get(4)?,
+                    }
+                )
+            })?
+            .collect::<Result<Vec<Launch>, _>>()
+            .context("Failed to execute launch history query.")?;
+
+        Ok(result)
+    }
+
+    /// Frecency of every launched project: each launch scores more the more recent it is.
+    pub fn usage() -> Result<HashMap<i32, Usage>> {
+        let conn = DB::connect()?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT id,
+                    SUM(CASE
+                        WHEN ?1 - timestamp < 4 * 86400 THEN 100
+                        WHEN ?1 - timestamp < 14 * 86400 THEN 70
+                        WHEN ?1 - timestamp < 31 * 86400 THEN 50
+                        WHEN ?1 - timestamp < 90 * 86400 THEN 30
+                        ELSE 10
+                    END),
+                    COUNT(*),
+                    MAX(timestamp)
+                FROM launches
+                GROUP BY id")
+            .context("Failed to prepare launch usage query.")?;
+
+        let result = stmt
+            .query_map([Self::now()], |row| {
+                Ok((row.get(0)?, Usage { score: row.get(1)?, count: row.get(2)?, last: row.get(3)? }))
+            })?
+            .collect::<Result<HashMap<i32, Usage>, _>>()
+            .context("Failed to execute launch usage query.")?;
+
+        Ok(result)
+    }
+
+    /// Describes how long ago a timestamp was, e.g. "3h ago".
+    pub fn ago(timestamp: i64) -> String {
+        match Self::now() - timestamp {
+            seconds if seconds < 60 => "just now".to_string(),
+            seconds if seconds < 3600 => format!("{}m ago", seconds / 60),
+            seconds if seconds < 86400 => format!("{}h ago", seconds / 3600),
+            seconds => format!("{}d ago", seconds / 86400),
+        }
+    }
+}
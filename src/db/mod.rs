@@ -1,128 +1,366 @@
 use rusqlite::Connection;
 use directories::ProjectDirs;
-use std::{fs, path::Path};
+use std::{fs, path::{Path, PathBuf}};
 use anyhow::{anyhow, Context, Result};
 
 pub(super) mod alias;
+pub(super) mod launch;
 pub(super) mod project;
+pub(super) mod revision;
+pub(super) mod running;
 pub(super) mod settings;
+pub(super) mod template;
+
+/// A schema change, applied to databases whose `user_version` is below its version.
+struct Migration {
+    version: u32,
+    sql: &'static str,
+}
+
+/// Every schema change in order, each version one above the last. Only ever append.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        sql: "
+        CREATE TABLE IF NOT EXISTS projects (
+            id INTEGER PRIMARY KEY AUTOINCREMENT, -- Auto-incrementing unique ID
+            name TEXT NOT NULL,                   -- Name of the project (string)
+            toml TEXT NOT NULL                    -- TOML content (string)
+        );
+
+        CREATE TABLE IF NOT EXISTS alias (
+            id INTEGER NOT NULL,          -- Foreign key referencing the 'projects' table
+            alias TEXT NOT NULL,          -- Alias name (string)
+            FOREIGN KEY (id) REFERENCES projects(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS settings (
+            uwsm BOOLEAN NOT NULL DEFAULT 0 CHECK (uwsm IN (0, 1))
+        );
+
+        INSERT INTO settings (uwsm)
+        SELECT 0 WHERE NOT EXISTS (SELECT 1 FROM settings);
+        ",
+    },
+    Migration {
+        version: 2,
+        sql: "
+        CREATE TABLE IF NOT EXISTS running (
+            id INTEGER NOT NULL,          -- Foreign key referencing the 'projects' table
+            name TEXT NOT NULL,           -- Name of the launched program
+            pid INTEGER,                  -- Process id, if a process was spawned
+            teardown TEXT,                -- Shell command undoing the launch
+            FOREIGN KEY (id) REFERENCES projects(id) ON DELETE CASCADE
+        );
+        ",
+    },
+    Migration {
+        version: 3,
+        sql: "
+        ALTER TABLE settings ADD COLUMN pre_launch TEXT;
+        ALTER TABLE settings ADD COLUMN post_launch TEXT;
+        ALTER TABLE settings ADD COLUMN on_failure TEXT;
+        ALTER TABLE settings ADD COLUMN on_exit TEXT;
+        ",
+    },
+    Migration {
+        version: 4,
+        sql: "
+        CREATE TABLE IF NOT EXISTS launches (
+            id INTEGER NOT NULL,          -- Foreign key referencing the 'projects' table
+            alias TEXT NOT NULL,          -- Name or alias the project was launched with
+            timestamp INTEGER NOT NULL,   -- Unix time of the launch
+            duration INTEGER NOT NULL,    -- Milliseconds the launch took
+            success BOOLEAN NOT NULL CHECK (success IN (0, 1)),
+            FOREIGN KEY (id) REFERENCES projects(id) ON DELETE CASCADE
+        );
+        ",
+    },
+    Migration {
+        version: 5,
+        sql: "
+        CREATE TABLE IF NOT EXISTS templates (
+            name TEXT PRIMARY KEY,        -- Name the template is used by
+            toml TEXT NOT NULL            -- TOML content with {{placeholders}}
+        );
+        ",
+    },
+    Migration {
+        version: 6,
+        sql: "
+        CREATE TABLE IF NOT EXISTS options (
+            key TEXT PRIMARY KEY,         -- Key of the setting in the registry
+            value TEXT NOT NULL           -- Validated value
+        );
+
+        INSERT OR IGNORE INTO options (key, value)
+        SELECT 'uwsm', 'true' FROM settings WHERE uwsm = 1;
+
+        INSERT OR IGNORE INTO options (key, value)
+        SELECT 'pre_launch', pre_launch FROM settings WHERE pre_launch IS NOT NULL;
+
+        INSERT OR IGNORE INTO options (key, value)
+        SELECT 'post_launch', post_launch FROM settings WHERE post_launch IS NOT NULL;
+
+        INSERT OR IGNORE INTO options (key, value)
+        SELECT 'on_failure', on_failure FROM settings WHERE on_failure IS NOT NULL;
+
+        INSERT OR IGNORE INTO options (key, value)
+        SELECT 'on_exit', on_exit FROM settings WHERE on_exit IS NOT NULL;
+        ",
+    },
+    Migration {
+        version: 7,
+        sql: "
+        CREATE TABLE IF NOT EXISTS project_revisions (
+            rev INTEGER PRIMARY KEY AUTOINCREMENT, -- Orders the revisions
+            id INTEGER NOT NULL,          -- Foreign key referencing the 'projects' table
+            timestamp INTEGER NOT NULL,   -- Unix time the configuration was replaced
+            toml TEXT NOT NULL,           -- The configuration before it was replaced
+            reason TEXT NOT NULL,         -- What replaced it: edit, import, revert
+            FOREIGN KEY (id) REFERENCES projects(id) ON DELETE CASCADE
+        );
+        ",
+    },
+    Migration {
+        version: 8,
+        sql: "
+        ALTER TABLE running ADD COLUMN started INTEGER; -- Start time of the process in clock ticks since boot
+        ",
+    },
+];
+
+/// The schema version this binary migrates databases to.
+const SCHEMA_VERSION: u32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
 
 pub struct DB {}
 impl DB {
-    fn connect() -> Result<Connection> {
+    /// devinit's data directory, created when missing.
+    pub fn data_dir() -> Result<PathBuf> {
         // Get the project directory using ProjectDirs
-        if let Some(proj_dirs) = ProjectDirs::from("com", "braniacs", "devinit") {
-            let data_dir = proj_dirs.data_dir();
-            let db_path = data_dir.join("devinit.db");
-
-            if !data_dir.exists() {
-                fs::create_dir_all(data_dir).context("Failed to create data directory")?;
-            }
-
-            // Open the database connection
-            let conn = Connection::open(&db_path).context("Failed to open database connection")?;
-
-            // Check if the database has been initialized
-            match Self::is_db_initialized(&conn){
-                Ok(true) => (),
-                Ok(false) |
-                Err(_) => {
-                    Self::init_db(&db_path)?;
-                }
-            }
-
-            // Open the database connection
-            let conn = Connection::open(&db_path).context("Failed to open database connection")?;
-
-            Self::update_db(&conn, None)?;
-            
-            Ok(conn)
-        } else {
-            Err(anyhow!("Failed to find or create project directories."))
+        let proj_dirs = ProjectDirs::from("com", "braniacs", "devinit")
+            .ok_or_else(|| anyhow!("Failed to find or create project directories."))?;
+        let data_dir = proj_dirs.data_dir();
+
+        if !data_dir.exists() {
+            fs::create_dir_all(data_dir).context("Failed to create data directory")?;
         }
+
+        Ok(data_dir.to_path_buf())
+    }
+
+    fn connect() -> Result<Connection> {
+        Self::open(&Self::data_dir()?.join("devinit.db"))
+    }
+
+    /// Makes the changes in a single transaction, so either all of them are stored or none.
+    pub fn transaction<T>(changes: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        let mut conn = Self::connect()?;
+        let tx = conn.transaction()?;
+
+        let result = changes(&tx)?;
+        tx.commit()?;
+
+        Ok(result)
     }
 
-    fn update_db(conn: &Connection, version: Option<u32>) -> Result<bool> {
-        let version: u32 = match version {
-            Some(v) => v,
-            None => conn.query_row(
-                "PRAGMA user_version;", 
-                [], 
-                |row| row.get::<_, u32>(0))?,
-        };
-        
-
-        match version {
-            0 => {
-                conn.execute("
-                CREATE TABLE IF NOT EXISTS settings (
-                    uwsm BOOLEAN NOT NULL DEFAULT 0 CHECK (uwsm IN (0, 1))
-                );
-
-                PRAGMA user_version = 1;
-                ", [])?;
-
-                conn.execute("
-                INSERT INTO settings (uwsm) VALUES (0);
-                ", [])?;
-
-                Self::update_db(conn, Some(1))
-            },
-            num if num >= 1 => Ok(true),
-            other => Err(anyhow!("No Database Version Env Set. Received {}", other))
+    /// Opens the database at `path` and brings it up to the schema this binary knows, backing
+    /// it up first when there is anything to migrate.
+    fn open(path: &Path) -> Result<Connection> {
+        let mut conn = Connection::open(path).context("Failed to open database connection")?;
+
+        // SQLite leaves foreign keys off, and with them the cascades removing a project's rows
+        conn.pragma_update(None, "foreign_keys", true)
+            .context("Failed to enable foreign keys.")?;
+
+        let version = Self::version(&conn)?;
+
+        if version > SCHEMA_VERSION {
+            return Err(anyhow!(
+                "The database at {} has schema version {}, but this devinit only knows up to {}. Upgrade devinit to use it.",
+                path.display(), version, SCHEMA_VERSION
+            ));
+        }
+
+        if version < SCHEMA_VERSION && Self::has_tables(&conn)? {
+            let backup = path.with_extension(format!("db.v{}.bak", version));
+
+            fs::copy(path, &backup)
+                .with_context(|| format!("Failed to back up the database to {} before migrating it.", backup.display()))?;
         }
+
+        Self::migrate(&mut conn)?;
+
+        Ok(conn)
+    }
+
+    fn version(conn: &Connection) -> Result<u32> {
+        conn.query_row("PRAGMA user_version;", [], |row| row.get(0))
+            .context("Failed to read the database schema version.")
     }
 
-    fn is_db_initialized(conn: &Connection) -> Result<bool> {
-        // Check if the 'projects' table exists
-        let mut stmt_projects = conn.prepare(
-            "SELECT name FROM sqlite_master WHERE type='table' AND name='projects';",
-        )?;
-        let projects_table_exists: bool = stmt_projects.exists([])?;
-    
-        // Check if the 'alias' table exists
-        let mut stmt_alias = conn.prepare(
-            "SELECT name FROM sqlite_master WHERE type='table' AND name='alias';",
-        )?;
-        let alias_table_exists: bool = stmt_alias.exists([])?;
-    
-        // The database is initialized only if both tables exist
-        Ok(projects_table_exists && alias_table_exists)
-    }
-
-    fn init_db(db_path: &Path) -> Result<()> {
-        // Open a connection to the database file
-        let conn = Connection::open(db_path).context("Failed to open database for initialization")?;
-
-        conn.execute("PRAGMA foreign_keys = ON;", []).unwrap();
-        
-        // Execute SQL to create the tables
-        match conn.execute(
-            "
-            CREATE TABLE projects (
-                id INTEGER PRIMARY KEY AUTOINCREMENT, -- Auto-incrementing unique ID
-                name TEXT NOT NULL,                   -- Name of the project (string)
-                toml TEXT NOT NULL                    -- TOML content (string)
-            );"
-            ,
-            []
-        ) {
-            Ok(_) => (),
-            Err(_) => (),
-        };
-
-        conn.execute(
-            "
-            -- Create the 'alias' table
-            CREATE TABLE alias (
-                id INTEGER NOT NULL,          -- Foreign key referencing the 'projects' table
-                alias TEXT NOT NULL,                  -- Alias name (string)
-                FOREIGN KEY (id) REFERENCES projects(id) ON DELETE CASCADE
-            );
-            ",
-            [],
-        )?;
+    fn has_tables(conn: &Connection) -> Result<bool> {
+        conn.prepare("SELECT name FROM sqlite_master WHERE type = 'table';")?
+            .exists([])
+            .context("Failed to inspect the database.")
+    }
+
+    /// Runs every migration newer than the database, each in a transaction that also bumps
+    /// `user_version`, so a failing migration leaves the database at the previous version.
+    fn migrate(conn: &mut Connection) -> Result<()> {
+        let version = Self::version(conn)?;
+
+        for migration in MIGRATIONS.iter().filter(|migration| migration.version > version) {
+            let tx = conn.transaction()?;
+
+            tx.execute_batch(migration.sql)
+                .and_then(|_| tx.pragma_update(None, "user_version", migration.version))
+                .with_context(|| format!("Failed to migrate the database to version {}.", migration.version))?;
+
+            tx.commit()?;
+        }
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dumps of databases as every earlier devinit left them, each holding a project with two aliases.
+    const FIXTURES: &[(u32, &str)] = &[
+        (0, include_str!("../../tests/fixtures/db/v0.sql")),
+        (1, include_str!("../../tests/fixtures/db/v1.sql")),
+        (2, include_str!("../../tests/fixtures/db/v2.sql")),
+        (3, include_str!("../../tests/fixtures/db/v3.sql")),
+        (4, include_str!("../../tests/fixtures/db/v4.sql")),
+        (5, include_str!("../../tests/fixtures/db/v5.sql")),
+        (6, include_str!("../../tests/fixtures/db/v6.sql")),
+        (7, include_str!("../../tests/fixtures/db/v7.sql")),
+        (8, include_str!("../../tests/fixtures/db/v8.sql")),
+    ];
+
+    fn fixture(version: u32) -> Connection {
+        let (_, sql) = FIXTURES.iter().find(|(fixture, _)| *fixture == version).unwrap();
+        let conn = Connection::open_in_memory().unwrap();
+
+        conn.execute_batch(sql).unwrap();
+        assert_eq!(DB::version(&conn).unwrap(), version);
+
+        conn
+    }
+
+    fn count(conn: &Connection, table: &str) -> u32 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap()
+    }
+
+    fn option(conn: &Connection, key: &str) -> Option<String> {
+        conn.query_row("SELECT value FROM options WHERE key = ?", [key], |row| row.get(0)).ok()
+    }
+
+    /// A database file of its own for tests that need one on disk.
+    pub(crate) fn temp_db(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("devinit-{}-{}.db", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+
+        path
+    }
+
+    #[test]
+    fn migrations_are_consecutive() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, index + 1);
+        }
+
+        assert_eq!(FIXTURES.last().unwrap().0, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn migrates_an_empty_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+
+        DB::migrate(&mut conn).unwrap();
+
+        assert_eq!(DB::version(&conn).unwrap(), SCHEMA_VERSION);
+        assert_eq!(count(&conn, "projects"), 0);
+        assert_eq!(count(&conn, "options"), 0);
+    }
+
+    #[test]
+    fn migrates_every_historical_version() {
+        for (version, _) in FIXTURES {
+            let mut conn = fixture(*version);
+
+            DB::migrate(&mut conn).unwrap();
+
+            assert_eq!(DB::version(&conn).unwrap(), SCHEMA_VERSION, "from version {}", version);
+            assert_eq!(count(&conn, "projects"), 1, "from version {}", version);
+            assert_eq!(count(&conn, "alias"), 2, "from version {}", version);
+
+            // uwsm was turned on from version 1 and hooks were set from version 3
+            let uwsm = (*version >= 1).then(|| "true".to_string());
+            let on_failure = (*version >= 3).then(|| "notify-send failed".to_string());
+
+            assert_eq!(option(&conn, "uwsm"), uwsm, "from version {}", version);
+            assert_eq!(option(&conn, "on_failure"), on_failure, "from version {}", version);
+        }
+    }
+
+    #[test]
+    fn moves_settings_into_options() {
+        let mut conn = fixture(5);
+
+        DB::migrate(&mut conn).unwrap();
+
+        // Only the settings that were set become options, NULL hooks stay unset
+        assert_eq!(count(&conn, "options"), 3);
+        assert_eq!(option(&conn, "uwsm").as_deref(), Some("true"));
+        assert_eq!(option(&conn, "pre_launch").as_deref(), Some("echo pre"));
+        assert_eq!(option(&conn, "on_failure").as_deref(), Some("notify-send failed"));
+        assert_eq!(option(&conn, "post_launch"), None);
+        assert_eq!(option(&conn, "on_exit"), None);
+    }
+
+    #[test]
+    fn failed_migration_rolls_back() {
+        let mut conn = fixture(2);
+
+        // Makes the last ALTER of version 3 fail after the others went through
+        conn.execute_batch("ALTER TABLE settings ADD COLUMN on_exit TEXT;").unwrap();
+
+        assert!(DB::migrate(&mut conn).is_err());
+        assert_eq!(DB::version(&conn).unwrap(), 2);
+        assert!(conn.prepare("SELECT pre_launch FROM settings").is_err());
+    }
+
+    #[test]
+    fn backs_up_before_migrating() {
+        let path = temp_db("backup");
+        Connection::open(&path).unwrap().execute_batch(FIXTURES[5].1).unwrap();
+
+        let conn = DB::open(&path).unwrap();
+        assert_eq!(DB::version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let backup = path.with_extension("db.v5.bak");
+        assert_eq!(DB::version(&Connection::open(&backup).unwrap()).unwrap(), 5);
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&backup).unwrap();
+    }
+
+    #[test]
+    fn refuses_newer_schemas() {
+        let path = temp_db("newer");
+        Connection::open(&path)
+            .unwrap()
+            .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+
+        let error = DB::open(&path).unwrap_err();
+        assert!(error.to_string().contains("Upgrade devinit"));
+        assert_eq!(DB::version(&Connection::open(&path).unwrap()).unwrap(), SCHEMA_VERSION + 1);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
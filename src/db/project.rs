@@ -1,6 +1,9 @@
-use rusqlite::ToSql;
 use anyhow::{Result, anyhow, Context};
-use crate::db::DB;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use crate::db::{DB, alias::Alias, launch::{Launch, Usage}};
+use crate::config::{self, ProjectConfig};
+use crate::matching::{suggestions, Match};
 
 pub struct Project {
     pub id: i32,
@@ -9,57 +12,138 @@ pub struct Project {
 }
 
 impl Project {
+    pub fn config(&self) -> Result<ProjectConfig> {
+        self.config_using(&Self::find)
+    }
+
+    /// The configuration, with the projects it extends looked up by `find`.
+    pub fn config_using(&self, find: &impl Fn(&str) -> Result<Project>) -> Result<ProjectConfig> {
+        let mut config: ProjectConfig = self.resolve_extends(find, &mut Vec::new())?
+            .try_into()
+            .map_err(|error: toml::de::Error| match config::did_you_mean(error.message()) {
+                Some(hint) => anyhow!("{}\n{}", error.message(), hint),
+                None => anyhow!(error),
+            })
+            .context("Failed to deserialize TOML configuration.")?;
+
+        // A removal with nothing to remove is not a program either
+        config.programs.list.retain(|program| program.remove != Some(true));
+
+        Ok(config)
+    }
+
+    /// The project's TOML with the projects it extends merged underneath.
+    pub fn resolved(&self) -> Result<toml::Value> {
+        self.resolve_extends(&Self::find, &mut Vec::new())
+    }
+
+    /// Merges in the chain of projects looked up by `find`, `seen` holding the ones on the way here.
+    fn resolve_extends(&self, find: &impl Fn(&str) -> Result<Project>, seen: &mut Vec<String>) -> Result<toml::Value> {
+        let mut value: toml::Value = toml::from_str(&self.toml)
+            .with_context(|| format!("Failed to parse the TOML configuration of {}.", self.name))?;
+
+        let Some(extends) = value.as_table_mut().and_then(|table| table.remove("extends")) else {
+            return Ok(value);
+        };
+
+        let extends = extends
+            .as_str()
+            .ok_or_else(|| anyhow!("extends in {} must be the name of a project.", self.name))?;
+
+        seen.push(self.name.to_string());
 
-    pub fn get(names: &[String]) -> Result<Self> {
-        if names.is_empty() {
-            return Err(anyhow!("At least one alias must be provided."));
+        let base = find(extends)
+            .with_context(|| format!("{} extends {}, which is not a project.", self.name, extends))?;
+
+        if seen.contains(&base.name) {
+            return Err(anyhow!("{} extends itself through {}.", base.name, seen.join(" -> ")));
         }
 
-        // Connect to the database
-        let conn = DB::connect().context("Unable to get project.")?;
+        Ok(config::merge(base.resolve_extends(find, seen)?, value))
+    }
 
-        // Create placeholders for the query
-        let placeholders: Vec<_> = names.iter().map(|_| "?").collect();
-        let placeholders_str = placeholders.join(",");
+    /// Finds a project by its exact name or alias.
+    pub fn find(name: &str) -> Result<Self> {
+        let conn = DB::connect()?;
 
-        // Prepare the SQL query
-        let query = format!(
-            "
-            SELECT p.id, p.name, p.toml 
-            FROM projects p
-            LEFT JOIN alias a ON p.id = a.id
-            WHERE a.alias IN ({}) OR p.name IN ({})
-            GROUP BY p.id
-            HAVING COUNT(DISTINCT CASE WHEN a.alias IN ({}) THEN a.alias ELSE NULL END) + 
-                COUNT(DISTINCT CASE WHEN p.name IN ({}) THEN p.name ELSE NULL END) = ?
-            LIMIT 1",
-            placeholders_str, placeholders_str, placeholders_str, placeholders_str
-        );
+        conn.query_row(
+            "SELECT p.id, p.name, p.toml
+                FROM projects p
+                WHERE p.name = ?1
+                OR ?1 IN (SELECT a.alias FROM alias a WHERE p.id = a.id)",
+            [name],
+            |row| Ok(Project { id: row.get(0)?, name: row.get(1)?, toml: row.get(2)? })
+        )
+        .map_err(|_| anyhow!("No project or alias named {}.", name))
+    }
 
-        let mut stmt = conn
-            .prepare(&query)
-            .context("Failed to prepare get project statement.")?;
+    /// Finds a project by name or alias, ignoring case and accepting prefixes, subsequences
+    /// and small typos. Equally good matches go to the most frecent project, and the lookup
+    /// is refused when that still leaves a tie.
+    pub fn resolve(query: &str) -> Result<Self> {
+        let projects = Self::get_all()?;
+        let id = Self::pick(query, &projects, &Alias::get_all()?, &Launch::usage()?)?;
+
+        projects
+            .into_iter()
+            .find(|project| project.id == id)
+            .ok_or(anyhow!("No project or alias matches {}.", query))
+    }
 
-        let names_len = &names.len();
-        
-        // Construct the parameters for the query
-        let params: Vec<&dyn ToSql> = names
+    /// The id of the project `query` resolves to among `projects` and their `aliases`.
+    fn pick(query: &str, projects: &[Project], aliases: &[Alias], usage: &HashMap<i32, Usage>) -> Result<i32> {
+        let names: Vec<(i32, &str)> = projects
             .iter()
-            .flat_map(|name| std::iter::repeat(name as &dyn ToSql).take(4)) // Repeat each name 4 times
-            .chain(std::iter::once(&names_len as &dyn ToSql)) // Append the count parameter
+            .map(|project| (project.id, project.name.as_str()))
+            .chain(aliases.iter().map(|alias| (alias.id, alias.alias.as_str())))
             .collect();
 
-        // Execute the query and map the result to a `Project`
-        let project = stmt.query_row(params.as_slice(), |row| {
-            Ok(Project {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                toml: row.get(2)?,
-            })
-        })
-        .context("Failed to map query to project.")?;
+        // Keep the best match of each project across its name and aliases
+        let mut best: HashMap<i32, Match> = HashMap::new();
+        for (id, name) in &names {
+            if let Some(found) = Match::of(query, name) {
+                best.entry(*id)
+                    .and_modify(|current| *current = (*current).min(found))
+                    .or_insert(found);
+            }
+        }
 
-        Ok(project)
+        let score = |id: &i32| usage.get(id).map(|usage| usage.score).unwrap_or(0);
+
+        // Best match first, then the most frecent
+        let mut ranked: Vec<(Match, i64, i32)> = best
+            .into_iter()
+            .map(|(id, found)| (found, -score(&id), id))
+            .collect();
+        ranked.sort();
+
+        let name = |id: i32| projects
+            .iter()
+            .find(|project| project.id == id)
+            .map(|project| project.name.to_string())
+            .unwrap_or_default();
+
+        match ranked.as_slice() {
+            [] => {
+                let suggestions = suggestions(query, names.iter().map(|(_, name)| *name));
+
+                if suggestions.is_empty() {
+                    Err(anyhow!("No project or alias matches {}.", query))
+                } else {
+                    Err(anyhow!("No project or alias matches {}. Did you mean {}?", query, suggestions.join(", ")))
+                }
+            }
+            [first, second, ..] if (first.0, first.1) == (second.0, second.1) => {
+                let tied: Vec<String> = ranked
+                    .iter()
+                    .take_while(|candidate| (candidate.0, candidate.1) == (first.0, first.1))
+                    .map(|candidate| name(candidate.2))
+                    .collect();
+
+                Err(anyhow!("{} is ambiguous, it could be any of: {}.", query, tied.join(", ")))
+            }
+            [first, ..] => Ok(first.2),
+        }
     }
 
     pub fn get_all() -> Result<Vec<Project>> {
@@ -85,15 +169,31 @@ impl Project {
         Ok(result)
     }
 
+    /// All projects, most frecent first and never launched ones alphabetically at the end.
+    pub fn get_ranked() -> Result<Vec<Project>> {
+        let mut projects = Self::get_all()?;
+
+        let usage = Launch::usage()?;
+        projects.sort_by(|a, b| {
+            let score = |project: &Project| usage.get(&project.id).map(|usage| usage.score).unwrap_or(0);
+            score(b).cmp(&score(a)).then_with(|| a.name.cmp(&b.name))
+        });
+
+        Ok(projects)
+    }
+
     pub fn add(name: String, toml: String) -> Result<i32> {
-        let conn = DB::connect()?;
+        Self::add_in(&DB::connect()?, name, toml)
+    }
 
+    /// Adds a project as part of the changes made on `conn`.
+    pub fn add_in(conn: &Connection, name: String, toml: String) -> Result<i32> {
         let mut stmt = conn
-            .prepare(&format!(
+            .prepare(
                 "
                 INSERT INTO projects(name, toml)
                 VALUES (?, ?)"
-            ))
+            )
             .context("Failed to prepare project add query.")?;
 
         stmt.execute(
@@ -116,17 +216,32 @@ impl Project {
         Ok(())
     }
 
-    pub fn replace_toml(id: i32, toml: String) -> Result<()> {
-        let mut conn = DB::connect()?;
+    /// Replaces the project's configuration, keeping the previous one as a revision
+    /// recording `reason`. Replacing it with the same text keeps no revision.
+    pub fn replace_toml(id: i32, toml: String, reason: &str) -> Result<()> {
+        DB::transaction(|conn| Self::replace_toml_in(conn, id, toml, reason))
+    }
 
-        let tx = conn.transaction()?;
+    /// Replaces the configuration as part of the changes made on `conn`.
+    pub fn replace_toml_in(conn: &Connection, id: i32, toml: String, reason: &str) -> Result<()> {
+        let previous: String = conn.query_row("SELECT toml FROM projects WHERE id = ?", [id], |row| row.get(0))
+            .context("Failed to retrieve the current configuration.")?;
 
-        tx.execute(
+        if previous == toml {
+            return Ok(());
+        }
+
+        conn.execute(
+            "INSERT INTO project_revisions (id, timestamp, toml, reason)
+                VALUES (?, ?, ?, ?)",
+            params![id, Launch::now(), previous, reason]
+        )
+        .context("Failed to record the previous configuration.")?;
+
+        conn.execute(
             "UPDATE projects SET toml = ? WHERE id = ?", 
         [toml, id.to_string()])?;
 
-        tx.commit()?;
-
         Ok(())
     }
 
@@ -144,4 +259,141 @@ impl Project {
 
         Ok(id)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn projects(tomls: &[(&str, &str)]) -> Vec<Project> {
+        tomls
+            .iter()
+            .enumerate()
+            .map(|(id, (name, toml))| Project { id: id as i32, name: name.to_string(), toml: toml.to_string() })
+            .collect()
+    }
+
+    fn resolve(projects: &[Project], name: &str) -> Result<toml::Value> {
+        let find = |name: &str| {
+            projects
+                .iter()
+                .find(|project| project.name == name)
+                .map(|project| Project { id: project.id, name: project.name.to_string(), toml: project.toml.to_string() })
+                .ok_or_else(|| anyhow!("No project or alias named {}.", name))
+        };
+
+        find(name)?.resolve_extends(&find, &mut Vec::new())
+    }
+
+    #[test]
+    fn merges_the_whole_chain() {
+        let projects = projects(&[
+            ("base", "root = \"/srv/base\"\n[hooks]\non_exit = \"echo bye\""),
+            ("web", "extends = \"base\"\n[hooks]\npre_launch = \"git pull\""),
+            ("shop", "extends = \"web\"\nroot = \"/srv/shop\""),
+        ]);
+
+        let resolved = resolve(&projects, "shop").unwrap();
+
+        assert_eq!(resolved["root"].as_str(), Some("/srv/shop"));
+        assert_eq!(resolved["hooks"]["pre_launch"].as_str(), Some("git pull"));
+        assert_eq!(resolved["hooks"]["on_exit"].as_str(), Some("echo bye"));
+        assert!(resolved.get("extends").is_none());
+    }
+
+    #[test]
+    fn refuses_extends_cycles() {
+        let projects = projects(&[
+            ("a", "extends = \"b\""),
+            ("b", "extends = \"c\""),
+            ("c", "extends = \"a\""),
+            ("self", "extends = \"self\""),
+        ]);
+
+        let error = resolve(&projects, "a").unwrap_err().to_string();
+        assert_eq!(error, "a extends itself through a -> b -> c.");
+
+        let error = resolve(&projects, "self").unwrap_err().to_string();
+        assert_eq!(error, "self extends itself through self.");
+    }
+
+    #[test]
+    fn names_a_missing_base() {
+        let projects = projects(&[("web", "extends = \"gone\"")]);
+
+        let error = resolve(&projects, "web").unwrap_err().to_string();
+        assert_eq!(error, "web extends gone, which is not a project.");
+    }
+
+    fn named(names: &[&str]) -> Vec<Project> {
+        projects(&names.iter().map(|name| (*name, "")).collect::<Vec<_>>())
+    }
+
+    fn pick(query: &str, projects: &[Project], aliases: &[Alias], scores: &[(i32, i64)]) -> Result<String> {
+        let usage = scores
+            .iter()
+            .map(|(id, score)| (*id, Usage { score: *score, ..Default::default() }))
+            .collect();
+
+        Project::pick(query, projects, aliases, &usage).map(|id| projects[id as usize].name.to_string())
+    }
+
+    #[test]
+    fn picks_the_best_match() {
+        let projects = named(&["web", "webapp", "api"]);
+        let aliases = [Alias { id: 2, alias: "backend".to_string() }];
+
+        assert_eq!(pick("WEB", &projects, &aliases, &[(1, 500)]).unwrap(), "web");
+        assert_eq!(pick("weba", &projects, &aliases, &[]).unwrap(), "webapp");
+        assert_eq!(pick("back", &projects, &aliases, &[]).unwrap(), "api");
+    }
+
+    #[test]
+    fn resolves_a_single_fuzzy_match() {
+        let projects = named(&["webapp", "api"]);
+
+        assert_eq!(pick("wbap", &projects, &[], &[]).unwrap(), "webapp");
+        assert_eq!(pick("wepapp", &projects, &[], &[]).unwrap(), "webapp");
+    }
+
+    #[test]
+    fn breaks_ties_by_frecency() {
+        let projects = named(&["api", "webapp", "webhooks"]);
+        let aliases = [Alias { id: 1, alias: "api".to_string() }];
+
+        // An exact name and an exact alias
+        assert_eq!(pick("api", &projects, &aliases, &[(1, 100)]).unwrap(), "webapp");
+        assert_eq!(pick("api", &projects, &aliases, &[(0, 100), (1, 70)]).unwrap(), "api");
+        assert_eq!(
+            pick("api", &projects, &aliases, &[]).unwrap_err().to_string(),
+            "api is ambiguous, it could be any of: api, webapp."
+        );
+
+        // Two subsequences
+        assert_eq!(pick("wb", &projects, &aliases, &[(2, 10)]).unwrap(), "webhooks");
+        assert!(pick("wb", &projects, &aliases, &[]).is_err());
+    }
+
+    #[test]
+    fn resolves_shared_prefixes_to_the_most_frecent() {
+        let projects = named(&["webapp", "webhooks", "worker"]);
+
+        assert_eq!(pick("we", &projects, &[], &[(0, 300)]).unwrap(), "webapp");
+        assert_eq!(pick("we", &projects, &[], &[(0, 100), (1, 170)]).unwrap(), "webhooks");
+        assert_eq!(
+            pick("we", &projects, &[], &[(0, 100), (1, 100)]).unwrap_err().to_string(),
+            "we is ambiguous, it could be any of: webapp, webhooks."
+        );
+    }
+
+    #[test]
+    fn suggests_names_when_nothing_matches() {
+        let projects = named(&["webapp", "api"]);
+
+        assert_eq!(
+            pick("apple", &projects, &[], &[]).unwrap_err().to_string(),
+            "No project or alias matches apple. Did you mean api?"
+        );
+        assert_eq!(pick("zzzzzz", &projects, &[], &[]).unwrap_err().to_string(), "No project or alias matches zzzzzz.");
+    }
+}
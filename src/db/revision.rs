@@ -0,0 +1,136 @@
+use anyhow::{anyhow, Result, Context};
+use rusqlite::Connection;
+use crate::db::DB;
+
+/// A configuration a project had before it was replaced.
+pub struct Revision {
+    /// Counts from 1, the oldest revision of the project.
+    pub number: u32,
+    pub time: String,
+    /// What replaced it: edit, import or "revert to <number>".
+    pub reason: String,
+    pub toml: String,
+}
+
+impl Revision {
+    /// The revisions of a project, oldest first.
+    pub fn get_all(id: i32) -> Result<Vec<Revision>> {
+        Self::get_all_in(&DB::connect()?, id)
+    }
+
+    fn get_all_in(conn: &Connection, id: i32) -> Result<Vec<Revision>> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT datetime(timestamp, 'unixepoch', 'localtime'), reason, toml
+                FROM project_revisions
+                WHERE id = ?
+                ORDER BY rev")
+            .context("Failed to prepare revision get_all query.")?;
+
+        let result: Vec<(String, String, String)> = stmt
+            .query_map([id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to execute revision get_all query.")?;
+
+        Ok(result
+            .into_iter()
+            .zip(1..)
+            .map(|((time, reason, toml), number)| Revision { number, time, reason, toml })
+            .collect())
+    }
+
+    pub fn get(id: i32, name: &str, number: u32) -> Result<Revision> {
+        let revisions = Self::get_all(id)?;
+        let count = revisions.len();
+
+        revisions
+            .into_iter()
+            .find(|revision| revision.number == number)
+            .ok_or_else(|| match count {
+                0 => anyhow!("{} has no revisions yet.", name),
+                1 => anyhow!("{} only has revision 1.", name),
+                count => anyhow!("{} has revisions 1 to {}, not {}.", name, count, number),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::project::Project;
+    use crate::db::tests::temp_db;
+    use std::fs;
+
+    fn tomls(revisions: &[Revision]) -> Vec<(u32, &str, &str)> {
+        revisions
+            .iter()
+            .map(|revision| (revision.number, revision.reason.as_str(), revision.toml.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn numbers_revisions_from_the_oldest() {
+        let path = temp_db("revisions-numbers");
+        let conn = DB::open(&path).unwrap();
+
+        let id = Project::add_in(&conn, "web".to_string(), "one".to_string()).unwrap();
+        Project::replace_toml_in(&conn, id, "two".to_string(), "edit").unwrap();
+        Project::replace_toml_in(&conn, id, "three".to_string(), "import").unwrap();
+
+        // Another project's revisions are numbered on their own
+        let other = Project::add_in(&conn, "api".to_string(), "a".to_string()).unwrap();
+        Project::replace_toml_in(&conn, other, "b".to_string(), "edit").unwrap();
+
+        assert_eq!(tomls(&Revision::get_all_in(&conn, id).unwrap()), [(1, "edit", "one"), (2, "import", "two")]);
+        assert_eq!(tomls(&Revision::get_all_in(&conn, other).unwrap()), [(1, "edit", "a")]);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn keeps_no_revision_of_the_same_text() {
+        let path = temp_db("revisions-same");
+        let conn = DB::open(&path).unwrap();
+
+        let id = Project::add_in(&conn, "web".to_string(), "one".to_string()).unwrap();
+        Project::replace_toml_in(&conn, id, "one".to_string(), "edit").unwrap();
+
+        assert!(Revision::get_all_in(&conn, id).unwrap().is_empty());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reverting_keeps_the_replaced_configuration() {
+        let path = temp_db("revisions-revert");
+        let conn = DB::open(&path).unwrap();
+
+        let id = Project::add_in(&conn, "web".to_string(), "one".to_string()).unwrap();
+        Project::replace_toml_in(&conn, id, "two".to_string(), "edit").unwrap();
+
+        let first = Revision::get_all_in(&conn, id).unwrap().remove(0);
+        Project::replace_toml_in(&conn, id, first.toml, "revert to 1").unwrap();
+
+        let current: String = conn.query_row("SELECT toml FROM projects WHERE id = ?", [id], |row| row.get(0)).unwrap();
+        assert_eq!(current, "one");
+        assert_eq!(tomls(&Revision::get_all_in(&conn, id).unwrap()), [(1, "edit", "one"), (2, "revert to 1", "two")]);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn removing_a_project_removes_its_revisions() {
+        let path = temp_db("revisions-remove");
+        let conn = DB::open(&path).unwrap();
+
+        let id = Project::add_in(&conn, "web".to_string(), "one".to_string()).unwrap();
+        Project::replace_toml_in(&conn, id, "two".to_string(), "edit").unwrap();
+
+        conn.execute("DELETE FROM projects WHERE id = ?", [id]).unwrap();
+
+        let left: u32 = conn.query_row("SELECT COUNT(*) FROM project_revisions", [], |row| row.get(0)).unwrap();
+        assert_eq!(left, 0);
+
+        fs::remove_file(path).unwrap();
+    }
+}
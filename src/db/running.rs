@@ -0,0 +1,88 @@
+use rusqlite::{params, OptionalExtension};
+use anyhow::{Result, Context};
+use crate::db::{DB, project::Project};
+use crate::process::stop::{same_process, start_time};
+
+/// A program launched for a project, kept so it can be stopped later.
+pub struct Running {
+    /// Tells apart the rows of a program launched more than once.
+    pub rowid: i64,
+    pub name: String,
+    pub pid: Option<i32>,
+    pub teardown: Option<String>,
+    /// When the process started, to tell it apart from a later one with the same pid.
+    pub started: Option<i64>,
+}
+
+impl Running {
+    pub fn get(id: i32) -> Result<Vec<Running>> {
+        let conn = DB::connect()?;
+
+        let mut stmt = conn
+            .prepare("SELECT rowid, name, pid, teardown, started FROM running WHERE id = ?")
+            .context("Failed to prepare running get query.")?;
+
+        let result: Vec<Running> = stmt
+            .query_map([id], |row| {
+                Ok(
+                    Running {
+                        rowid: row.get(0)?,
+                        name: row.get(1)?,
+                        pid: row.get(2)?,
+                        teardown: row.get(3)?,
+                        started: row.get(4)?,
+                    }
+                )
+            })?
+            .collect::<Result<Vec<Running>, _>>()
+            .context("Failed to execute running get query.")?;
+
+        Ok(result)
+    }
+
+    /// Finds which project and program a pid was launched as, when it still is that program.
+    pub fn owner(pid: i32) -> Result<Option<(Project, Running)>> {
+        let conn = DB::connect()?;
+
+        let result = conn.query_row(
+            "SELECT p.id, p.name, p.toml, r.rowid, r.name, r.pid, r.teardown, r.started
+                FROM running r
+                JOIN projects p ON p.id = r.id
+                WHERE r.pid = ?",
+            [pid],
+            |row| Ok((
+                Project { id: row.get(0)?, name: row.get(1)?, toml: row.get(2)? },
+                Running { rowid: row.get(3)?, name: row.get(4)?, pid: row.get(5)?, teardown: row.get(6)?, started: row.get(7)? },
+            ))
+        )
+        .optional()
+        .context("Failed to execute running owner query.")?;
+
+        Ok(result.filter(|(_, entry)| same_process(pid, entry.started)))
+    }
+
+    pub fn add(id: i32, name: &str, pid: Option<i32>, teardown: Option<String>) -> Result<()> {
+        let conn = DB::connect()?;
+
+        let started = pid.and_then(start_time);
+
+        conn.execute(
+            "INSERT INTO running (id, name, pid, teardown, started)
+                VALUES(?, ?, ?, ?, ?)",
+            params![id, name, pid, teardown, started]
+        )
+        .context("Failed to record running program.")?;
+
+        Ok(())
+    }
+
+    /// Stops tracking this one launch, leaving other launches of the same program recorded.
+    pub fn remove(&self) -> Result<()> {
+        let conn = DB::connect()?;
+
+        conn.execute("DELETE FROM running WHERE rowid = ?", [self.rowid])
+            .context("Failed to remove running program.")?;
+
+        Ok(())
+    }
+}
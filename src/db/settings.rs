@@ -1,52 +1,342 @@
-use anyhow::{Result,Context};
+use anyhow::{anyhow, Result, Context};
+use rusqlite::OptionalExtension;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
 
+use crate::config::{expand_home, Editor, Hooks};
 use crate::db::DB;
+use crate::matching::suggestions;
+use crate::process::stop::parse_duration;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// The type of a setting, which decides what values it accepts.
+#[derive(Debug, Clone, Copy)]
+pub enum Kind {
+    Bool,
+    String,
+    Enum(&'static [&'static str]),
+    Path,
+    Duration,
+    /// A program found on PATH followed by its arguments.
+    Command,
+}
+
+impl Kind {
+    pub fn name(&self) -> String {
+        match self {
+            Kind::Bool => "bool".to_string(),
+            Kind::String => "string".to_string(),
+            Kind::Enum(values) => values.join("|"),
+            Kind::Path => "path".to_string(),
+            Kind::Duration => "duration".to_string(),
+            Kind::Command => "command".to_string(),
+        }
+    }
+
+    /// The value a bool setting flips to from `current`, where unset counts as false.
+    pub fn flipped(current: Option<&str>) -> String {
+        (current != Some("true")).to_string()
+    }
+
+    /// Checks a value and brings it into the form it is stored in.
+    fn normalize(&self, value: &str) -> Result<String> {
+        match self {
+            Kind::Bool => match value.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Ok("true".to_string()),
+                "false" | "no" | "off" | "0" => Ok("false".to_string()),
+                _ => Err(anyhow!("Expected true or false, got {}.", value)),
+            },
+            Kind::String => Ok(value.to_string()),
+            Kind::Enum(values) => values
+                .iter()
+                .find(|allowed| allowed.eq_ignore_ascii_case(value))
+                .map(|allowed| allowed.to_string())
+                .ok_or_else(|| anyhow!("Expected one of {}, got {}.", values.join(", "), value)),
+            Kind::Path => {
+                let path = expand_home(value);
+
+                if !Path::new(&path).is_absolute() {
+                    return Err(anyhow!("Expected an absolute path, got {}.", value));
+                }
+
+                Ok(path)
+            }
+            Kind::Duration => parse_duration(value).map(|_| value.to_string()),
+            Kind::Command => {
+                let words = Editor::words(value)?;
+                let program = words
+                    .first()
+                    .ok_or_else(|| anyhow!("Expected a command, got nothing."))?;
+
+                if !on_path(program) {
+                    return Err(anyhow!("{} was not found.", program));
+                }
+
+                Ok(value.trim().to_string())
+            }
+        }
+    }
+}
+
+/// Whether `program` can be run: an executable file when it contains a slash, otherwise one
+/// found in a directory of PATH, the way a shell looks it up.
+fn on_path(program: &str) -> bool {
+    let executable = |path: &Path| {
+        path.metadata()
+            .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+    };
+
+    if program.contains('/') {
+        return executable(Path::new(program));
+    }
+
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| executable(&dir.join(program))))
+}
+
+/// A global option `devinit set` knows about.
+#[derive(Debug)]
+pub struct Setting {
+    pub key: &'static str,
+    pub kind: Kind,
+    pub default: Option<&'static str>,
+    pub description: &'static str,
+}
+
+pub const REGISTRY: &[Setting] = &[
+    Setting {
+        key: "uwsm",
+        kind: Kind::Bool,
+        default: Some("false"),
+        description: "Launch programs through `uwsm app --`.",
+    },
+    Setting {
+        key: "editor",
+        kind: Kind::Command,
+        default: None,
+        description: "Editor for add, edit and templates, like `code --wait`. $VISUAL or $EDITOR when unset.",
+    },
+    Setting {
+        key: "output_mode",
+        kind: Kind::Enum(&["inherit", "null"]),
+        default: Some("inherit"),
+        description: "Where the output of detached programs without an output_mode goes.",
+    },
+    Setting {
+        key: "stop_timeout",
+        kind: Kind::Duration,
+        default: Some("10s"),
+        description: "How long stopping waits for programs without a stop_timeout before SIGKILL.",
+    },
+    Setting {
+        key: "history_dir",
+        kind: Kind::Path,
+        default: None,
+        description: "Where `devinit shell` keeps project histories, the data directory when unset.",
+    },
+    Setting {
+        key: "pre_launch",
+        kind: Kind::String,
+        default: None,
+        description: "Hook run before every launch, a failure aborts the launch.",
+    },
+    Setting {
+        key: "post_launch",
+        kind: Kind::String,
+        default: None,
+        description: "Hook run after every successful launch.",
+    },
+    Setting {
+        key: "on_failure",
+        kind: Kind::String,
+        default: None,
+        description: "Hook run when a launch fails.",
+    },
+    Setting {
+        key: "on_exit",
+        kind: Kind::String,
+        default: None,
+        description: "Hook run once a project's programs exited or were stopped.",
+    },
+];
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Settings {
+    /// Launch programs through `uwsm app --`.
     pub uwsm: bool,
 }
 
+impl Setting {
+    /// Looks a setting up by key, suggesting close keys when there is none.
+    pub fn find(key: &str) -> Result<&'static Setting> {
+        if let Some(setting) = REGISTRY.iter().find(|setting| setting.key == key) {
+            return Ok(setting);
+        }
+
+        match suggestions(key, REGISTRY.iter().map(|setting| setting.key)).first() {
+            Some(suggestion) => Err(anyhow!("Unknown setting {}. Did you mean {}?", key, suggestion)),
+            None => Err(anyhow!("Unknown setting {}, see `devinit settings list`.", key)),
+        }
+    }
+}
+
 impl Settings {
     pub fn get_all() -> Result<Self> {
+        Ok(Settings {
+            uwsm: Self::flag("uwsm")?,
+        })
+    }
+
+    /// The value of a setting, its default when it was never set.
+    pub fn get(key: &str) -> Result<Option<String>> {
+        let setting = Setting::find(key)?;
+
+        Ok(Self::stored(key)?.or(setting.default.map(String::from)))
+    }
+
+    /// Every setting that was set, by key, read in a single query.
+    pub fn stored_all() -> Result<HashMap<String, String>> {
         let conn = DB::connect()?;
 
-        Ok(conn.query_row(
-            "SELECT * FROM settings",
-            [],
-            |row| Ok(Settings {
-                uwsm: row.get(0)?,
-            }))
-            .context("Failed to retrieve general settings.")?
+        let mut stmt = conn
+            .prepare("SELECT key, value FROM options")
+            .context("Failed to retrieve settings.")?;
+
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect())
+            .context("Failed to retrieve settings.")
+    }
+
+    pub fn flag(key: &str) -> Result<bool> {
+        Ok(Self::get(key)?.as_deref() == Some("true"))
+    }
+
+    /// The value a setting was set to, None when it is unset.
+    pub fn stored(key: &str) -> Result<Option<String>> {
+        let conn = DB::connect()?;
+
+        conn.query_row("SELECT value FROM options WHERE key = ?", [key], |row| row.get(0))
+            .optional()
+            .context("Failed to retrieve setting.")
+    }
+
+    pub fn set(key: &str, value: &str) -> Result<String> {
+        let setting = Setting::find(key)?;
+        let value = setting.kind
+            .normalize(value)
+            .with_context(|| format!("Invalid value for {}.", key))?;
+
+        let conn = DB::connect()?;
+
+        conn.execute(
+            "INSERT INTO options (key, value) VALUES (?1, ?2)
+                ON CONFLICT (key) DO UPDATE SET value = ?2",
+            [key, &value]
         )
+        .context("Failed to store setting.")?;
+
+        Ok(value)
     }
 
-    pub fn set_uwsm(value: Option<String>) -> Result<()> {
+    pub fn unset(key: &str) -> Result<()> {
+        Setting::find(key)?;
+
         let conn = DB::connect()?;
 
-        match value {
-            Some(val) => {
-                let mut stmt = conn.prepare(
-                    "
-                    UPDATE settings
-                    SET uwsm = ?
-                    ")?;
+        conn.execute("DELETE FROM options WHERE key = ?", [key])
+            .context("Failed to unset setting.")?;
 
-                stmt.execute([val])?;
+        Ok(())
+    }
 
-                Ok(())
-            },
-            None => {
-                let mut stmt = conn.prepare("
-                UPDATE settings
-                SET uwsm = ((uwsm | 1) - (uwsm & 1))
-                ")?;
+    pub fn get_hooks() -> Result<Hooks> {
+        let mut stored = Self::stored_all()?;
+
+        Ok(Hooks {
+            pre_launch: stored.remove("pre_launch"),
+            post_launch: stored.remove("post_launch"),
+            on_failure: stored.remove("on_failure"),
+            on_exit: stored.remove("on_exit"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_bools() {
+        for value in ["true", "YES", "on", "1"] {
+            assert_eq!(Kind::Bool.normalize(value).unwrap(), "true");
+        }
+
+        for value in ["false", "No", "off", "0"] {
+            assert_eq!(Kind::Bool.normalize(value).unwrap(), "false");
+        }
+
+        assert!(Kind::Bool.normalize("maybe").is_err());
+    }
 
-                stmt.execute([])?;
+    #[test]
+    fn flips_bools() {
+        assert_eq!(Kind::flipped(Some("true")), "false");
+        assert_eq!(Kind::flipped(Some("false")), "true");
+        assert_eq!(Kind::flipped(None), "true");
+    }
+
+    #[test]
+    fn only_accepts_enum_values() {
+        let kind = Kind::Enum(&["inherit", "null"]);
 
-                Ok(())
+        assert_eq!(kind.normalize("Null").unwrap(), "null");
+        assert_eq!(
+            kind.normalize("log").unwrap_err().to_string(),
+            "Expected one of inherit, null, got log."
+        );
+    }
+
+    #[test]
+    fn validates_durations() {
+        for value in ["500ms", "10s", "2m", "1h", "3"] {
+            assert_eq!(Kind::Duration.normalize(value).unwrap(), value);
+        }
+
+        assert!(Kind::Duration.normalize("ten").is_err());
+        assert!(Kind::Duration.normalize("10 days").is_err());
+        assert!(Kind::Duration.normalize("99999999999999999999999").is_err());
+    }
+
+    #[test]
+    fn requires_absolute_paths() {
+        assert_eq!(Kind::Path.normalize("/srv/history").unwrap(), "/srv/history");
+        assert!(Kind::Path.normalize("relative/history").is_err());
+    }
+
+    #[test]
+    fn looks_commands_up_on_path() {
+        assert_eq!(Kind::Command.normalize(" sh -c true ").unwrap(), "sh -c true");
+        assert!(Kind::Command.normalize("/bin/sh").is_ok());
+        assert_eq!(
+            Kind::Command.normalize("devinit-no-such-editor --wait").unwrap_err().to_string(),
+            "devinit-no-such-editor was not found."
+        );
+
+        // Paths with a slash are not looked up, and must be executable files
+        assert!(Kind::Command.normalize("./sh").is_err());
+        assert!(Kind::Command.normalize("/bin").is_err());
+        assert!(Kind::Command.normalize(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")).is_err());
+    }
+
+    #[test]
+    fn every_default_is_valid() {
+        for setting in REGISTRY {
+            if let Some(default) = setting.default {
+                assert_eq!(setting.kind.normalize(default).unwrap(), default, "default of {}", setting.key);
             }
         }
     }
-}
\ No newline at end of file
+}
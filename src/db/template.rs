@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Context, Result};
+
+use crate::db::DB;
+
+/// A reusable project configuration with `{{placeholders}}` filled in when a project is added from it.
+#[derive(Debug)]
+pub struct Template {
+    pub name: String,
+    pub toml: String,
+}
+
+impl Template {
+    pub fn get(name: &str) -> Result<Template> {
+        let conn = DB::connect()?;
+
+        conn.query_row(
+            "SELECT name, toml FROM templates WHERE name = ?",
+            [name],
+            |row| Ok(Template { name: row.get(0)?, toml: row.get(1)? })
+        )
+        .map_err(|_| anyhow!("No template named {}.", name))
+    }
+
+    pub fn get_all() -> Result<Vec<Template>> {
+        let conn = DB::connect()?;
+
+        let mut stmt = conn
+            .prepare("SELECT name, toml FROM templates ORDER BY name")
+            .context("Failed to prepare template get all query.")?;
+
+        let result: Vec<Template> = stmt
+            .query_map([], |row| Ok(Template { name: row.get(0)?, toml: row.get(1)? }))?
+            .collect::<Result<Vec<Template>, _>>()
+            .context("Failed to execute template get all query.")?;
+
+        Ok(result)
+    }
+
+    pub fn add(name: &str, toml: String) -> Result<()> {
+        let conn = DB::connect()?;
+
+        conn.execute("INSERT INTO templates (name, toml) VALUES (?, ?)", [name, &toml])
+            .with_context(|| format!("Failed to add template {}, does it already exist?", name))?;
+
+        Ok(())
+    }
+
+    pub fn replace_toml(name: &str, toml: String) -> Result<()> {
+        let conn = DB::connect()?;
+
+        conn.execute("UPDATE templates SET toml = ? WHERE name = ?", [&toml, name])
+            .context("Failed to update template.")?;
+
+        Ok(())
+    }
+
+    pub fn remove(name: &str) -> Result<()> {
+        let conn = DB::connect()?;
+
+        let removed = conn.execute("DELETE FROM templates WHERE name = ?", [name])
+            .context("Failed to remove template.")?;
+
+        if removed == 0 {
+            return Err(anyhow!("No template named {}.", name));
+        }
+
+        Ok(())
+    }
+
+    /// The distinct placeholder names in the template, in order of appearance.
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut placeholders: Vec<String> = Vec::new();
+        let mut rest = self.toml.as_str();
+
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}") else { break };
+
+            let name = rest[start + 2..start + end].trim().to_string();
+            if !name.is_empty() && !placeholders.contains(&name) {
+                placeholders.push(name);
+            }
+
+            rest = &rest[start + end + 2..];
+        }
+
+        placeholders
+    }
+
+    /// The template with every placeholder replaced by its value.
+    pub fn render(&self, values: &[(String, String)]) -> String {
+        let mut result = String::with_capacity(self.toml.len());
+        let mut rest = self.toml.as_str();
+
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}") else { break };
+
+            let name = rest[start + 2..start + end].trim();
+
+            result.push_str(&rest[..start]);
+            match values.iter().find(|(placeholder, _)| placeholder == name) {
+                Some((_, value)) => result.push_str(value),
+                None => result.push_str(&rest[start..start + end + 2]),
+            }
+
+            rest = &rest[start + end + 2..];
+        }
+
+        result.push_str(rest);
+        result
+    }
+}
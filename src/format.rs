@@ -0,0 +1,243 @@
+use anyhow::{Result, Context};
+use serde_json::Value as Json;
+use std::collections::HashMap;
+use toml::Value;
+
+use crate::config::{self, Hooks, Limits, Program, Programs, ProjectConfig, ShellConfig};
+use crate::db::settings::Settings;
+
+/// The configuration new projects start from: a small working project, followed by every other
+/// field commented out with an example value and documented from the config types.
+pub fn project_format() -> Result<String> {
+    render(false)
+}
+
+/// What the template starts with enabled.
+fn starter() -> ProjectConfig {
+    ProjectConfig {
+        root: Some("~/code/my-project".to_string()),
+        programs: Programs {
+            list: vec![Program {
+                name: "editor".to_string(),
+                path: "nvim".to_string(),
+                working_directory: Some("~/code/my-project".to_string()),
+                args: Some(vec![".".to_string()]),
+                ..Default::default()
+            }],
+        },
+        ..Default::default()
+    }
+}
+
+/// A value for every field, so the template can show them all.
+fn example() -> ProjectConfig {
+    let hooks = || Hooks {
+        pre_launch: Some("git pull --ff-only".to_string()),
+        post_launch: Some("notify-send launched".to_string()),
+        on_failure: Some("notify-send 'launch failed'".to_string()),
+        on_exit: Some("echo exited with $DEVINIT_EXIT_CODE".to_string()),
+    };
+
+    ProjectConfig {
+        extends: Some("base-project".to_string()),
+        unset: Some(vec!["hooks.on_exit".to_string()]),
+        root: Some("~/code/my-project".to_string()),
+        programs: Programs {
+            list: vec![Program {
+                name: "server".to_string(),
+                path: "cargo".to_string(),
+                kind: Some("process".to_string()),
+                working_directory: Some("~/code/my-project".to_string()),
+                args: Some(vec!["run".to_string()]),
+                commands: Some(vec!["echo started".to_string()]),
+                output_mode: Some("inherit".to_string()),
+                env: Some(HashMap::from([("RUST_LOG".to_string(), "${RUST_LOG:-debug}".to_string())])),
+                env_file: Some(".env".to_string()),
+                auto_close: Some(true),
+                set_active_window: Some(false),
+                settings: Some(Settings { uwsm: false }),
+                compose_file: Some("docker-compose.yml".to_string()),
+                services: Some(vec!["db".to_string()]),
+                project_name: Some("my-project".to_string()),
+                ports: Some(vec![8080]),
+                hooks: Some(hooks()),
+                stop_signal: Some("SIGINT".to_string()),
+                stop_timeout: Some("10s".to_string()),
+                limits: Some(Limits {
+                    nice: Some(10),
+                    ionice: Some("best-effort".to_string()),
+                    ionice_level: Some(7),
+                    nofile: Some(4096),
+                    address_space: Some(8_589_934_592),
+                    cpu_time: Some(3600),
+                    cpu_affinity: Some(vec![0, 1]),
+                }),
+                remove: Some(false),
+                unset: Some(vec!["env.DEBUG".to_string()]),
+            }],
+        },
+        hooks: Some(hooks()),
+        shell: Some(ShellConfig {
+            program: Some("server".to_string()),
+            history: Some(true),
+            stop_on_exit: Some(true),
+        }),
+    }
+}
+
+/// Writes the example out line by line, documenting each field and commenting out whatever
+/// the starter does not set, unless `everything` is enabled.
+fn render(everything: bool) -> Result<String> {
+    let docs = descriptions();
+    let starter = Value::try_from(starter()).context("Failed to serialize the starter configuration.")?;
+    let example = toml::to_string(&example()).context("Failed to serialize the example configuration.")?;
+
+    let mut format = String::new();
+    let mut table: Vec<&str> = Vec::new();
+    let mut table_enabled = true;
+
+    let mut push = |doc: Option<&String>, line: &str, enabled: bool| {
+        if let Some(doc) = doc {
+            format.push_str(&format!("# {}\n", doc));
+        }
+
+        if enabled {
+            format.push_str(&format!("{}\n", line));
+        } else {
+            format.push_str(&format!("# {}\n", line));
+        }
+    };
+
+    for line in example.lines() {
+        if line.is_empty() {
+            push(None, "", true);
+            continue;
+        }
+
+        if line.starts_with('[') {
+            table = line.trim_matches(['[', ']']).split('.').collect();
+            table_enabled = everything || lookup(&starter, &table).is_some();
+
+            push(docs.get(&table.join(".")), line, table_enabled);
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(" = ") else {
+            push(None, line, table_enabled);
+            continue;
+        };
+
+        let mut path = table.clone();
+        path.push(key);
+
+        let doc = docs.get(&path.join("."));
+
+        match lookup(&starter, &path) {
+            Some(starter) if table_enabled => push(doc, &format!("{} = {}", key, starter), true),
+            _ => push(doc, &format!("{} = {}", key, value), everything),
+        }
+    }
+
+    Ok(format.trim_start().to_string())
+}
+
+/// Looks up a dotted path in a TOML value, going into the first entry of arrays of tables.
+fn lookup<'a>(value: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, key| {
+        let value = match value {
+            Value::Array(values) => values.first()?,
+            value => value,
+        };
+
+        value.get(key)
+    })
+}
+
+/// Doc comments of every config field by dotted path, like `programs.list.name`.
+fn descriptions() -> HashMap<String, String> {
+    let schema = config::schema();
+    let mut docs = HashMap::new();
+
+    describe(schema.as_value(), schema.as_value(), "", &mut docs);
+
+    docs
+}
+
+fn describe(root: &Json, schema: &Json, path: &str, docs: &mut HashMap<String, String>) {
+    let Some(properties) = schema.get("properties").and_then(Json::as_object) else { return };
+
+    for (key, property) in properties {
+        let path = if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+
+        if let Some(doc) = property.get("description").and_then(Json::as_str) {
+            docs.insert(path.to_string(), doc.to_string());
+        }
+
+        if let Some(definition) = definition(root, property) {
+            describe(root, definition, &path, docs);
+        }
+    }
+}
+
+/// The definition a property refers to, through Options and Vecs.
+fn definition<'a>(root: &'a Json, schema: &'a Json) -> Option<&'a Json> {
+    if let Some(reference) = schema.get("$ref").and_then(Json::as_str) {
+        return root.pointer(reference.trim_start_matches('#'));
+    }
+
+    ["anyOf", "allOf"]
+        .iter()
+        .filter_map(|key| schema.get(key).and_then(Json::as_array))
+        .flatten()
+        .chain(schema.get("items"))
+        .find_map(|schema| definition(root, schema))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::ProcessManager;
+
+    #[test]
+    fn project_format_parses() {
+        let format = project_format().unwrap();
+        let config: ProjectConfig = toml::from_str(&format).unwrap();
+
+        assert_eq!(config.programs.list.len(), 1);
+    }
+
+    #[test]
+    fn starter_programs_run_in_an_absolute_directory() {
+        let config: ProjectConfig = toml::from_str(&project_format().unwrap()).unwrap();
+
+        for program in &config.programs.list {
+            let command = ProcessManager::command(program).unwrap();
+            let directory = command.get_current_dir().unwrap();
+
+            assert!(directory.is_absolute(), "{} runs in {}", program.name, directory.display());
+        }
+    }
+
+    #[test]
+    fn every_field_parses_when_enabled() {
+        let format = render(true).unwrap();
+
+        toml::from_str::<ProjectConfig>(&format).unwrap();
+        assert!(!format.lines().any(|line| line.starts_with("# [") || line.starts_with("# name =")));
+    }
+
+    #[test]
+    fn every_field_is_documented() {
+        let format = render(true).unwrap();
+        let lines: Vec<&str> = format.lines().collect();
+
+        for (index, line) in lines.iter().enumerate() {
+            let documented = index > 0 && lines[index - 1].starts_with("# ");
+            let map_entry = line.starts_with("RUST_LOG");
+
+            if !line.is_empty() && !line.starts_with('#') && !map_entry {
+                assert!(documented, "{} has no documentation", line);
+            }
+        }
+    }
+}
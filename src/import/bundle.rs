@@ -0,0 +1,91 @@
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::db::{project::Project, alias::Alias, launch::Recorded};
+
+/// Projects written out by `devinit export`, in the form `devinit import` reads back.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Bundle {
+    #[serde(default)]
+    pub projects: Vec<Exported>,
+}
+
+/// A project with its aliases and launches. The revisions of its configuration and whatever
+/// it has running are left behind, they only make sense on the machine they were made on.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Exported {
+    pub name: String,
+
+    #[serde(default)]
+    pub aliases: Vec<String>,
+
+    /// The configuration exactly as stored, comments and all.
+    pub toml: String,
+
+    /// Launch history, which the frecency ranking is computed from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub launches: Vec<Recorded>,
+}
+
+impl Exported {
+    pub fn of(project: Project, aliases: &[Alias], launches: &mut HashMap<i32, Vec<Recorded>>) -> Self {
+        Exported {
+            aliases: aliases
+                .iter()
+                .filter(|alias| alias.id == project.id)
+                .map(|alias| alias.alias.to_string())
+                .collect(),
+            launches: launches.remove(&project.id).unwrap_or_default(),
+            name: project.name,
+            toml: project.toml,
+        }
+    }
+}
+
+impl Bundle {
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).context("Failed to serialize the exported projects.")
+    }
+
+    /// Reads a bundle file, or every .toml file of a directory written by `devinit export --dir`.
+    pub fn read(path: &Path) -> Result<Self> {
+        if !path.is_dir() {
+            return Self::read_file(path);
+        }
+
+        let mut files: Vec<_> = fs::read_dir(path)
+            .with_context(|| format!("Failed to read directory {}.", path.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| file.extension().is_some_and(|extension| extension == "toml"))
+            .collect();
+        files.sort();
+
+        let mut bundle = Bundle::default();
+
+        for file in files {
+            bundle.projects.extend(Self::read_file(&file)?.projects);
+        }
+
+        Ok(bundle)
+    }
+
+    fn read_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}.", path.display()))?;
+
+        let bundle: Bundle = toml::from_str(&contents)
+            .with_context(|| format!("{} is not a devinit export.", path.display()))?;
+
+        for project in &bundle.projects {
+            toml::from_str::<toml::Value>(&project.toml)
+                .with_context(|| format!("The configuration of {} in {} is not valid TOML.", project.name, path.display()))?;
+        }
+
+        Ok(bundle)
+    }
+}
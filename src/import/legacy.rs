@@ -0,0 +1,164 @@
+use anyhow::{anyhow, Result, Context};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::import::{Imported, directory};
+use crate::import::bundle::{Bundle, Exported};
+
+/// The config.toml of the old layout, mapping project files to comma separated aliases.
+#[derive(Debug, Default, Deserialize)]
+struct LegacyConfig {
+    #[serde(default)]
+    project_aliases: HashMap<String, String>,
+}
+
+/// A projects/<Name>.toml of the old layout.
+#[derive(Debug, Deserialize)]
+struct LegacyProject {
+    #[serde(default)]
+    commands: Vec<String>,
+}
+
+/// Translates the config.toml and projects/*.toml layout devinit used before sqlite.
+/// Each project becomes a single shell program running its commands, and a leading
+/// `cd` becomes the project root, relative to the home directory like the goto script ran it.
+pub fn read(dir: &Path) -> Result<Bundle> {
+    let config_file = dir.join("config.toml");
+    let config: LegacyConfig = match fs::read_to_string(&config_file) {
+        Ok(contents) => toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}.", config_file.display()))?,
+        Err(_) => LegacyConfig::default(),
+    };
+
+    let projects_dir = dir.join("projects");
+    let mut files: Vec<_> = fs::read_dir(&projects_dir)
+        .with_context(|| format!("Failed to read {}, is {} a legacy config directory?", projects_dir.display(), dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|file| file.extension().is_some_and(|extension| extension == "toml"))
+        .collect();
+    files.sort();
+
+    let mut bundle = Bundle::default();
+
+    for file in files {
+        let name = file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .ok_or_else(|| anyhow!("{} has no project name.", file.display()))?;
+
+        let contents = fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}.", file.display()))?;
+        let legacy: LegacyProject = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}.", file.display()))?;
+
+        let imported = convert(&name, legacy.commands)
+            .with_context(|| format!("Failed to convert {}.", file.display()))?;
+
+        let aliases = config.project_aliases
+            .get(&name)
+            .map(|aliases| {
+                aliases
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|alias| !alias.is_empty() && *alias != name)
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        bundle.projects.push(Exported {
+            name,
+            aliases,
+            toml: toml::to_string(&imported.config)
+                .context("Failed to serialize migrated configuration.")?,
+            launches: Vec::new(),
+        });
+    }
+
+    for name in config.project_aliases.keys() {
+        if !bundle.projects.iter().any(|project| project.name == *name) {
+            eprintln!("{} has aliases in config.toml but no projects/{}.toml, skipping it.", name, name);
+        }
+    }
+
+    Ok(bundle)
+}
+
+fn convert(name: &str, mut commands: Vec<String>) -> Result<Imported> {
+    let root = match commands.first().and_then(|command| command.trim().strip_prefix("cd ")) {
+        Some(path) => {
+            let home = std::env::var("HOME").context("HOME is not set.")?;
+            let root = directory(path.trim().trim_matches('"'), Path::new(&home));
+
+            commands.remove(0);
+            Some(root)
+        }
+        None => None,
+    };
+
+    let program = Imported::shell_program(name.to_string(), root.clone(), commands);
+
+    let mut imported = Imported::new(None, vec![program])?;
+    imported.config.root = root;
+
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn home(path: &str) -> String {
+        format!("{}/{}", std::env::var("HOME").unwrap(), path)
+    }
+
+    #[test]
+    fn turns_a_leading_cd_into_the_root() {
+        let imported = convert("web", vec!["cd code/web".to_string(), "npm start".to_string()]).unwrap();
+        let program = &imported.config.programs.list[0];
+
+        assert_eq!(imported.config.root, Some(home("code/web")));
+        assert_eq!(program.working_directory, Some(home("code/web")));
+        assert_eq!(program.commands.as_deref().unwrap(), ["npm start"]);
+
+        let imported = convert("api", vec!["cd \"/srv/my api\"".to_string(), "cargo run".to_string()]).unwrap();
+        assert_eq!(imported.config.root.as_deref(), Some("/srv/my api"));
+    }
+
+    #[test]
+    fn keeps_commands_without_a_leading_cd() {
+        let imported = convert("api", vec!["cargo run".to_string(), "cd elsewhere".to_string()]).unwrap();
+        let program = &imported.config.programs.list[0];
+
+        assert_eq!(imported.config.root, None);
+        assert_eq!(program.working_directory, None);
+        assert_eq!(program.commands.as_deref().unwrap(), ["cargo run", "cd elsewhere"]);
+    }
+
+    #[test]
+    fn reads_projects_with_their_aliases() {
+        let dir = std::env::temp_dir().join(format!("devinit-legacy-{}", std::process::id()));
+        fs::create_dir_all(dir.join("projects")).unwrap();
+
+        fs::write(dir.join("config.toml"), "[project_aliases]\nweb = \"w, site,,web\"\napi = \"a\"\ngone = \"g\"\n").unwrap();
+        fs::write(dir.join("projects/web.toml"), "commands = [\"cd code/web\", \"npm start\"]\n").unwrap();
+        fs::write(dir.join("projects/api.toml"), "commands = [\"cargo run\"]\n").unwrap();
+        fs::write(dir.join("projects/notes.txt"), "not a project").unwrap();
+
+        let bundle = read(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        let bundle = bundle.unwrap();
+
+        let names: Vec<&str> = bundle.projects.iter().map(|project| project.name.as_str()).collect();
+        assert_eq!(names, ["api", "web"]);
+
+        // Blank entries and the project's own name are dropped
+        assert_eq!(bundle.projects[0].aliases, ["a"]);
+        assert_eq!(bundle.projects[1].aliases, ["w", "site"]);
+
+        let web: toml::Value = toml::from_str(&bundle.projects[1].toml).unwrap();
+        assert_eq!(web["root"].as_str(), Some(home("code/web").as_str()));
+    }
+}
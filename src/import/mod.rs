@@ -0,0 +1,99 @@
+use anyhow::{anyhow, Result};
+use serde_yaml::Value;
+use std::path::Path;
+
+use crate::config::{expand_home, Hooks, Program, Programs, ProjectConfig};
+use crate::process::ProcessManager;
+
+pub(super) mod bundle;
+pub(super) mod legacy;
+pub(super) mod procfile;
+pub(super) mod tmuxinator;
+pub(super) mod tmuxp;
+
+/// A project translated from another tool's configuration.
+#[derive(Debug)]
+pub struct Imported {
+    /// The name the source file gives the project, if any.
+    pub name: Option<String>,
+    pub config: ProjectConfig,
+}
+
+impl Imported {
+    fn new(name: Option<String>, programs: Vec<Program>) -> Result<Self> {
+        if programs.is_empty() {
+            return Err(anyhow!("Nothing to import: no programs were found."));
+        }
+
+        Ok(Imported {
+            name,
+            config: ProjectConfig {
+                extends: None,
+                unset: None,
+                root: None,
+                programs: Programs { list: programs },
+                hooks: None,
+                shell: None,
+            },
+        })
+    }
+
+    /// A program that feeds its commands to a shell, the way a tmux pane would run them.
+    fn shell_program(name: String, working_directory: Option<String>, commands: Vec<String>) -> Program {
+        Program {
+            name,
+            path: "sh".to_string(),
+            working_directory,
+            commands: Some(commands),
+            ..Default::default()
+        }
+    }
+}
+
+/// Resolves a directory relative to the imported file's directory.
+fn directory(path: &str, base: &Path) -> String {
+    let expanded = expand_home(path);
+
+    if Path::new(&expanded).is_absolute() {
+        return expanded;
+    }
+
+    match expanded.trim_start_matches("./") {
+        "" | "." => base.to_string_lossy().to_string(),
+        relative => base.join(relative).to_string_lossy().to_string(),
+    }
+}
+
+/// Reads a YAML value holding a single command or a list of them.
+fn yaml_commands(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(command)) => vec![command.to_string()],
+        Some(Value::Sequence(commands)) => commands
+            .iter()
+            .flat_map(|command| yaml_commands(Some(command)))
+            .collect(),
+        Some(Value::Number(number)) => vec![number.to_string()],
+        Some(Value::Bool(flag)) => vec![flag.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+fn yaml_str<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+/// Project wide startup commands become a pre_launch hook, run from the root when there is one.
+fn startup_hooks(root: Option<&str>, commands: &[String]) -> Option<Hooks> {
+    if commands.is_empty() {
+        return None;
+    }
+
+    let commands = commands.join(" && ");
+
+    let pre_launch = match root {
+        Some(root) => format!("cd {} && {}", ProcessManager::shell_quote(root), commands),
+        None => commands,
+    };
+
+    Some(Hooks { pre_launch: Some(pre_launch), ..Default::default() })
+}
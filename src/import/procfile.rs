@@ -0,0 +1,66 @@
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+use crate::config::Program;
+use crate::import::Imported;
+
+/// Translates a Procfile, where each `name: command` line becomes a program.
+pub fn parse(contents: &str, base: &Path) -> Result<Imported> {
+    let mut programs = Vec::new();
+
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, command) = line
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Line {} is not of the form <name>: <command>.", number + 1))?;
+
+        programs.push(Program {
+            name: name.trim().to_string(),
+            path: "sh".to_string(),
+            working_directory: Some(base.to_string_lossy().to_string()),
+            args: Some(vec!["-c".to_string(), command.trim().to_string()]),
+            ..Default::default()
+        });
+    }
+
+    let mut imported = Imported::new(None, programs)?;
+    imported.config.root = Some(base.to_string_lossy().to_string());
+
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_line_becomes_a_program() {
+        let procfile = "# processes\nweb: bundle exec rails s -p $PORT\n\nworker:   sidekiq -C config.yml\n";
+        let imported = parse(procfile, Path::new("/srv/app")).unwrap();
+        let programs = &imported.config.programs.list;
+
+        assert_eq!(imported.config.root.as_deref(), Some("/srv/app"));
+        assert_eq!(programs.len(), 2);
+
+        assert_eq!(programs[0].name, "web");
+        assert_eq!(programs[0].path, "sh");
+        assert_eq!(
+            programs[0].args,
+            Some(vec!["-c".to_string(), "bundle exec rails s -p $PORT".to_string()])
+        );
+        assert_eq!(programs[1].working_directory.as_deref(), Some("/srv/app"));
+        assert_eq!(programs[1].args.as_ref().unwrap()[1], "sidekiq -C config.yml");
+    }
+
+    #[test]
+    fn rejects_lines_without_a_name() {
+        let error = parse("web: rails s\njust a command\n", Path::new("/srv")).unwrap_err();
+
+        assert!(error.to_string().contains("Line 2"));
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::{anyhow, Result, Context};
+use serde_yaml::Value;
+use std::path::Path;
+
+use crate::import::{directory, startup_hooks, yaml_commands, yaml_str, Imported};
+
+/// Translates a tmuxinator project, turning every window or pane into a program.
+pub fn parse(contents: &str, base: &Path) -> Result<Imported> {
+    let yaml: Value = serde_yaml::from_str(contents).context("Failed to parse tmuxinator YAML.")?;
+
+    let root = yaml_str(&yaml, "root")
+        .or_else(|| yaml_str(&yaml, "project_root"))
+        .map(|root| directory(root, base));
+    let pre_window = yaml_commands(yaml.get("pre_window"));
+
+    let windows = yaml
+        .get("windows")
+        .or_else(|| yaml.get("tabs"))
+        .and_then(Value::as_sequence)
+        .ok_or_else(|| anyhow!("tmuxinator config has no windows."))?;
+
+    let mut programs = Vec::new();
+
+    for window in windows {
+        // Each window is a single entry map of its name to its definition
+        let Some((name, definition)) = window.as_mapping().and_then(|map| map.iter().next()) else { continue };
+        let name = match name {
+            Value::String(name) => name.to_string(),
+            other => serde_yaml::to_string(other)?.trim().to_string(),
+        };
+
+        let window_root = yaml_str(definition, "root")
+            .map(|window_root| directory(window_root, root.as_deref().map(Path::new).unwrap_or(base)))
+            .or_else(|| root.clone());
+
+        let panes: Vec<Vec<String>> = match definition.get("panes").and_then(Value::as_sequence) {
+            Some(panes) => panes.iter().map(pane_commands).collect(),
+            None if definition.is_mapping() => vec![Vec::new()],
+            None => vec![yaml_commands(Some(definition))],
+        };
+
+        let numbered = panes.len() > 1;
+
+        for (index, commands) in panes.into_iter().enumerate() {
+            // An empty pane is just an interactive shell, which has nothing to launch
+            if commands.is_empty() {
+                continue;
+            }
+
+            let name = if numbered { format!("{}-{}", name, index + 1) } else { name.to_string() };
+            let commands = pre_window.iter().cloned().chain(commands).collect();
+
+            programs.push(Imported::shell_program(name, window_root.clone(), commands));
+        }
+    }
+
+    let mut imported = Imported::new(yaml_str(&yaml, "name").map(String::from), programs)?;
+
+    // tmuxinator runs `pre` once before any window starts and gives up when it fails. That is a
+    // pre_launch hook: as commands of a program it would race the windows it has to prepare, and
+    // prepended to every program like pre_window it would run once per pane.
+    let startup: Vec<String> = [yaml.get("on_project_start"), yaml.get("pre")]
+        .into_iter()
+        .flat_map(yaml_commands)
+        .collect();
+
+    imported.config.hooks = startup_hooks(root.as_deref(), &startup);
+    imported.config.root = root;
+
+    Ok(imported)
+}
+
+/// A pane is a command, a list of commands, or a map of a pane name to its commands.
+fn pane_commands(pane: &Value) -> Vec<String> {
+    match pane {
+        Value::Mapping(map) => map.values().flat_map(|commands| yaml_commands(Some(commands))).collect(),
+        other => yaml_commands(Some(other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: &str = "
+name: shop
+root: app
+pre_window: source .env
+on_project_start: echo starting
+pre: docker start db
+windows:
+  - editor: vim
+  - server:
+      root: api
+      panes:
+        - cargo run
+        - - npm install
+          - npm start
+  - shell:
+";
+
+    #[test]
+    fn windows_and_panes_become_programs() {
+        let imported = parse(PROJECT, Path::new("/srv")).unwrap();
+        let programs = &imported.config.programs.list;
+
+        assert_eq!(imported.name.as_deref(), Some("shop"));
+        assert_eq!(imported.config.root.as_deref(), Some("/srv/app"));
+
+        let names: Vec<&str> = programs.iter().map(|program| program.name.as_str()).collect();
+        assert_eq!(names, ["editor", "server-1", "server-2"]);
+
+        assert_eq!(programs[0].working_directory.as_deref(), Some("/srv/app"));
+        assert_eq!(programs[0].commands, Some(vec!["source .env".to_string(), "vim".to_string()]));
+        assert_eq!(programs[1].working_directory.as_deref(), Some("/srv/app/api"));
+        assert_eq!(
+            programs[2].commands,
+            Some(vec!["source .env".to_string(), "npm install".to_string(), "npm start".to_string()])
+        );
+    }
+
+    #[test]
+    fn pre_runs_once_before_launch() {
+        let imported = parse(PROJECT, Path::new("/srv")).unwrap();
+        let hooks = imported.config.hooks.unwrap();
+
+        assert_eq!(hooks.pre_launch.as_deref(), Some("cd /srv/app && echo starting && docker start db"));
+    }
+
+    #[test]
+    fn requires_windows() {
+        assert!(parse("name: empty\n", Path::new("/srv")).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::{anyhow, Result, Context};
+use serde_yaml::Value;
+use std::path::Path;
+
+use crate::import::{directory, startup_hooks, yaml_commands, yaml_str, Imported};
+
+/// Translates a tmuxp session, turning every pane into a program.
+pub fn parse(contents: &str, base: &Path) -> Result<Imported> {
+    let yaml: Value = serde_yaml::from_str(contents).context("Failed to parse tmuxp YAML.")?;
+
+    let root = yaml_str(&yaml, "start_directory").map(|root| directory(root, base));
+    let session_before = yaml_commands(yaml.get("shell_command_before"));
+
+    let windows = yaml
+        .get("windows")
+        .and_then(Value::as_sequence)
+        .ok_or_else(|| anyhow!("tmuxp config has no windows."))?;
+
+    let mut programs = Vec::new();
+
+    for (window_index, window) in windows.iter().enumerate() {
+        let name = yaml_str(window, "window_name")
+            .map(String::from)
+            .unwrap_or_else(|| format!("window-{}", window_index + 1));
+
+        let window_root = yaml_str(window, "start_directory")
+            .map(|window_root| directory(window_root, root.as_deref().map(Path::new).unwrap_or(base)))
+            .or_else(|| root.clone());
+        let window_before = yaml_commands(window.get("shell_command_before"));
+
+        let panes: Vec<(Option<String>, Vec<String>)> = match window.get("panes").and_then(Value::as_sequence) {
+            Some(panes) => panes
+                .iter()
+                .map(|pane| {
+                    let pane_root = yaml_str(pane, "start_directory")
+                        .map(|pane_root| directory(pane_root, window_root.as_deref().map(Path::new).unwrap_or(base)));
+
+                    match pane {
+                        Value::Mapping(_) => (pane_root, yaml_commands(pane.get("shell_command"))),
+                        // tmuxp's shorthands for an empty pane
+                        Value::String(shorthand) if shorthand == "blank" || shorthand == "pane" => (None, Vec::new()),
+                        other => (None, yaml_commands(Some(other))),
+                    }
+                })
+                .collect(),
+            None => vec![(None, yaml_commands(window.get("shell_command")))],
+        };
+
+        let numbered = panes.len() > 1;
+
+        for (index, (pane_root, commands)) in panes.into_iter().enumerate() {
+            // An empty pane is just an interactive shell, which has nothing to launch
+            if commands.is_empty() {
+                continue;
+            }
+
+            let name = if numbered { format!("{}-{}", name, index + 1) } else { name.to_string() };
+            let commands = session_before
+                .iter()
+                .chain(&window_before)
+                .cloned()
+                .chain(commands)
+                .collect();
+
+            programs.push(Imported::shell_program(name, pane_root.or_else(|| window_root.clone()), commands));
+        }
+    }
+
+    let mut imported = Imported::new(yaml_str(&yaml, "session_name").map(String::from), programs)?;
+
+    imported.config.hooks = startup_hooks(root.as_deref(), &yaml_commands(yaml.get("before_script")));
+    imported.config.root = root;
+
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION: &str = "
+session_name: blog
+start_directory: ./site
+before_script: ./bootstrap.sh
+shell_command_before: nvm use
+windows:
+  - window_name: dev
+    shell_command_before: export PORT=4000
+    panes:
+      - shell_command: npm run dev
+      - start_directory: /var/log
+        shell_command:
+          - tail -f app.log
+      - blank
+  - shell_command: htop
+";
+
+    #[test]
+    fn panes_become_programs() {
+        let imported = parse(SESSION, Path::new("/home/me")).unwrap();
+        let programs = &imported.config.programs.list;
+
+        assert_eq!(imported.name.as_deref(), Some("blog"));
+        assert_eq!(imported.config.root.as_deref(), Some("/home/me/site"));
+
+        let names: Vec<&str> = programs.iter().map(|program| program.name.as_str()).collect();
+        assert_eq!(names, ["dev-1", "dev-2", "window-2"]);
+
+        assert_eq!(
+            programs[0].commands,
+            Some(vec!["nvm use".to_string(), "export PORT=4000".to_string(), "npm run dev".to_string()])
+        );
+        assert_eq!(programs[0].working_directory.as_deref(), Some("/home/me/site"));
+        assert_eq!(programs[1].working_directory.as_deref(), Some("/var/log"));
+        assert_eq!(programs[2].commands, Some(vec!["nvm use".to_string(), "htop".to_string()]));
+    }
+
+    #[test]
+    fn before_script_runs_before_launch() {
+        let imported = parse(SESSION, Path::new("/home/me")).unwrap();
+        let hooks = imported.config.hooks.unwrap();
+
+        assert_eq!(hooks.pre_launch.as_deref(), Some("cd /home/me/site && ./bootstrap.sh"));
+    }
+}
@@ -2,20 +2,44 @@ mod branch;
 mod cli;
 mod db;
 mod config;
+mod format;
+mod import;
+mod matching;
+mod picker;
+mod process;
 
 use branch::{
     Branch,
+    cd::CdCommand,
+    completions::{CompleteCommand, CompletionsCommand},
     default::DefaultCommand,
     list::ListCommand,
     edit::EditCommand,
+    history::HistoryCommand,
     view::ViewCommand,
+    revisions::{DiffCommand, HistoryConfigCommand, RevertCommand},
+    run::RunCommand,
+    schema::SchemaCommand,
+    settings::{GetCommand, SettingsListCommand, UnsetCommand},
+    shell::ShellCommand,
+    shell_init::ShellInitCommand,
+    stop::StopCommand,
 };
 pub use cli::{
     Cli,
     Commands,
     AddCommand,
+    ExecCommand,
+    ImportCommand,
+    ExportCommand,
+    MigrateLegacyCommand,
+    Conflict,
+    ImportFormat,
     RemoveCommand,
-    SetCommand
+    SetCommand,
+    SettingsAction,
+    TemplateCommand,
+    TemplateAction,
 };
 
 use clap::Parser;
@@ -25,27 +49,53 @@ fn main() -> Result<()> {
     // Parse the CLI arguments
     let cli = Cli::parse();
 
+    if let Some(editor) = cli.editor {
+        config::Editor::override_with(editor);
+    }
+
     let command = match cli.command {
         Some(cmd) => cmd, // 
         None => {
-            if let Some(project_name) = cli.project_name {
-                Commands::Default { alias: project_name }
-            } else {
-                eprintln!("Error: You must provide a project name or a valid subcommand.");
-                std::process::exit(1);
+            match cli.project_name {
+                Some(project_name) if cli.attach => Commands::Run { alias: project_name },
+                Some(project_name) => Commands::Default { alias: Some(project_name) },
+                // Without a name the project is picked interactively
+                None => Commands::Default { alias: None },
             }
         }
     };
 
     // Dispatch based on the subcommand
     let branch: Box<dyn Branch> = match command {
-            Commands::Default { alias } => Box::new(DefaultCommand { alias: Some(alias) }),
+            Commands::Default { alias } => Box::new(DefaultCommand { alias }),
             Commands::Add(add_command) => Box::new(add_command),
             Commands::Remove(remove_command) => Box::new(remove_command),
-            Commands::View { alias} => Box::new(ViewCommand { alias }),
+            Commands::View { alias, resolved } => Box::new(ViewCommand { alias, resolved }),
             Commands::List  => Box::new(ListCommand {}),
+            Commands::History { alias } => Box::new(HistoryCommand { alias }),
             Commands::Set(set_command) => Box::new(set_command),
+            Commands::Get { key } => Box::new(GetCommand { key }),
+            Commands::Unset { key } => Box::new(UnsetCommand { key }),
+            Commands::Settings(settings_command) => match settings_command.action {
+                SettingsAction::List => Box::new(SettingsListCommand {}),
+            },
             Commands::Edit { alias } => Box::new(EditCommand { alias }),
+            Commands::HistoryConfig { alias } => Box::new(HistoryConfigCommand { alias }),
+            Commands::Diff { alias, rev } => Box::new(DiffCommand { alias, rev }),
+            Commands::Revert { alias, rev } => Box::new(RevertCommand { alias, rev }),
+            Commands::Template(template_command) => Box::new(template_command),
+            Commands::Import(import_command) => Box::new(import_command),
+            Commands::Export(export_command) => Box::new(export_command),
+            Commands::MigrateLegacy(migrate_command) => Box::new(migrate_command),
+            Commands::Run { alias } => Box::new(RunCommand { alias }),
+            Commands::Exec(exec_command) => Box::new(exec_command),
+            Commands::Stop { alias } => Box::new(StopCommand { alias }),
+            Commands::Schema { taplo } => Box::new(SchemaCommand { taplo }),
+            Commands::Cd { alias } => Box::new(CdCommand { alias }),
+            Commands::Shell { alias } => Box::new(ShellCommand { alias }),
+            Commands::ShellInit { shell } => Box::new(ShellInitCommand { shell }),
+            Commands::Completions { shell } => Box::new(CompletionsCommand { shell }),
+            Commands::Complete { shell, words } => Box::new(CompleteCommand { shell, words }),
         };
     
 
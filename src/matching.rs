@@ -0,0 +1,115 @@
+/// How well a name or alias matches what was typed, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Match {
+    Exact,
+    Prefix,
+    Subsequence,
+    Typo(usize),
+}
+
+impl Match {
+    /// Matches `query` against `candidate`, ignoring case.
+    pub fn of(query: &str, candidate: &str) -> Option<Match> {
+        let query = query.to_lowercase();
+        let candidate = candidate.to_lowercase();
+
+        if query == candidate {
+            Some(Match::Exact)
+        } else if candidate.starts_with(&query) {
+            Some(Match::Prefix)
+        } else if is_subsequence(&query, &candidate) {
+            Some(Match::Subsequence)
+        } else {
+            let distance = edit_distance(&query, &candidate);
+
+            // Allow roughly one typo per three characters
+            (distance <= (query.chars().count() / 3).max(1)).then_some(Match::Typo(distance))
+        }
+    }
+}
+
+fn is_subsequence(query: &str, candidate: &str) -> bool {
+    let mut chars = candidate.chars();
+    query.chars().all(|c| chars.any(|other| other == c))
+}
+
+/// Levenshtein distance between two strings.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// The closest candidates to a query that matched nothing, for "did you mean" hints.
+pub fn suggestions<'a>(query: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let query = query.to_lowercase();
+
+    let mut scored: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&query, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= query.chars().count().max(2) / 2 + 1)
+        .collect();
+
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored.into_iter().take(3).map(|(_, candidate)| candidate).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_from_exact_to_typos() {
+        assert_eq!(Match::of("WebApp", "webapp"), Some(Match::Exact));
+        assert_eq!(Match::of("web", "webapp"), Some(Match::Prefix));
+        assert_eq!(Match::of("wbap", "webapp"), Some(Match::Subsequence));
+        assert_eq!(Match::of("wepapp", "webapp"), Some(Match::Typo(1)));
+        assert_eq!(Match::of("xyz", "webapp"), None);
+    }
+
+    #[test]
+    fn allows_a_typo_per_three_characters() {
+        assert_eq!(Match::of("ap", "api"), Some(Match::Prefix));
+        assert_eq!(Match::of("apo", "api"), Some(Match::Typo(1)));
+        assert_eq!(Match::of("aqo", "api"), None);
+        assert_eq!(Match::of("wehbapq", "webapp"), Some(Match::Typo(2)));
+    }
+
+    #[test]
+    fn ranks_better_matches_first() {
+        assert!(Match::Exact < Match::Prefix);
+        assert!(Match::Prefix < Match::Subsequence);
+        assert!(Match::Subsequence < Match::Typo(1));
+        assert!(Match::Typo(1) < Match::Typo(2));
+    }
+
+    #[test]
+    fn counts_edits() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("flaw", "lawn"), 2);
+    }
+
+    #[test]
+    fn suggests_the_closest_candidates() {
+        let candidates = ["webapp", "webhooks", "api", "apps", "worker"];
+
+        assert_eq!(suggestions("apo", candidates), ["api", "apps"]);
+        assert_eq!(suggestions("Worker2", candidates), ["worker"]);
+        assert!(suggestions("database", candidates).is_empty());
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::{anyhow, Result};
+use dialoguer::FuzzySelect;
+use std::collections::HashMap;
+use std::io::{self, IsTerminal};
+
+use crate::db::{project::Project, alias::Alias};
+
+pub struct Picker {}
+
+impl Picker {
+    /// Lets the user fuzzy search their projects, most recently used first.
+    pub fn project(prompt: &str) -> Result<Project> {
+        if !io::stdin().is_terminal() {
+            return Err(anyhow!("You must provide a project name or a valid subcommand."));
+        }
+
+        let mut projects = Project::get_ranked()?;
+
+        if projects.is_empty() {
+            return Err(anyhow!("No projects yet, add one with `devinit add <name>`."));
+        }
+
+        let mut alias_map: HashMap<i32, Vec<String>> = HashMap::new();
+        for alias in Alias::get_all()? {
+            alias_map.entry(alias.id).or_default().push(alias.alias);
+        }
+
+        // Aliases are shown, and searchable, next to the project name
+        let items: Vec<String> = projects
+            .iter()
+            .map(|project| match alias_map.get(&project.id) {
+                Some(aliases) => format!("{}  ({})", project.name, aliases.join(", ")),
+                None => project.name.to_string(),
+            })
+            .collect();
+
+        let selection = FuzzySelect::new()
+            .with_prompt(prompt)
+            .items(&items)
+            .default(0)
+            .interact_opt()?
+            .ok_or(anyhow!("No project selected."))?;
+
+        Ok(projects.swap_remove(selection))
+    }
+}
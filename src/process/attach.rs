@@ -0,0 +1,246 @@
+use anyhow::{anyhow, Result, Context};
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+use std::os::unix::process::ExitStatusExt;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::config::Program;
+use crate::process::{ProcessManager, stop::{signal_group, StopPolicy}};
+
+const COLORS: [u8; 6] = [36, 33, 32, 35, 34, 31];
+const POLL: Duration = Duration::from_millis(100);
+
+/// Last SIGINT/SIGTERM delivered to devinit, 0 when none is pending.
+static SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+extern "C" fn record_signal(signal: libc::c_int) {
+    SIGNAL.store(signal, Ordering::SeqCst);
+}
+
+/// A program running in the foreground under devinit.
+struct Attached {
+    name: String,
+    child: Child,
+    readers: Vec<JoinHandle<()>>,
+    status: Option<ExitStatus>,
+    policy: StopPolicy,
+    /// When the grace period of a requested stop runs out.
+    deadline: Option<Instant>,
+    killed: bool,
+}
+
+/// Foreground session multiplexing the output of every program, like foreman.
+pub struct Session {
+    programs: Vec<Attached>,
+    teardowns: Vec<(String, String)>,
+    width: usize,
+    color: bool,
+}
+
+impl Session {
+    pub fn new(programs: &[Program]) -> Self {
+        // SAFETY: the handler only stores into an atomic, which is async-signal-safe.
+        unsafe {
+            libc::signal(libc::SIGINT, record_signal as *const () as libc::sighandler_t);
+            libc::signal(libc::SIGTERM, record_signal as *const () as libc::sighandler_t);
+        }
+
+        Session {
+            programs: Vec::new(),
+            teardowns: Vec::new(),
+            width: programs.iter().map(|program| program.name.len()).max().unwrap_or(0),
+            color: io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        }
+    }
+
+    fn prefix(&self, name: &str) -> String {
+        let label = format!("{:width$} |", name, width = self.width);
+
+        if self.color {
+            let color = COLORS[self.programs.len() % COLORS.len()];
+            format!("\x1b[{}m{}\x1b[0m", color, label)
+        } else {
+            label
+        }
+    }
+
+    /// Spawns a program in its own process group with its output streamed through devinit.
+    pub fn spawn(&mut self, program: &Program) -> Result<()> {
+        let policy = StopPolicy::new(Some(program))?;
+        let mut cmd: Command = ProcessManager::command(program)?;
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = ProcessManager::spawn(&mut cmd, program)?;
+        let prefix = self.prefix(&program.name);
+
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(Self::stream(stdout, prefix.clone(), false));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(Self::stream(stderr, prefix, true));
+        }
+
+        self.programs.push(Attached {
+            name: program.name.to_string(),
+            child,
+            readers,
+            status: None,
+            policy,
+            deadline: None,
+            killed: false,
+        });
+
+        Ok(())
+    }
+
+    /// Registers a shell command to run once the session ends.
+    pub fn teardown(&mut self, name: &str, command: String) {
+        self.teardowns.push((name.to_string(), command));
+    }
+
+    fn stream(source: impl Read + Send + 'static, prefix: String, stderr: bool) -> JoinHandle<()> {
+        thread::spawn(move || {
+            let mut reader = BufReader::new(source);
+            let mut line = Vec::new();
+
+            while let Ok(read) = reader.read_until(b'\n', &mut line) {
+                if read == 0 {
+                    break;
+                }
+
+                let text = String::from_utf8_lossy(&line);
+                let text = text.trim_end_matches(['\n', '\r']);
+
+                // Errors writing to a closed terminal are not worth dying over
+                let _ = if stderr {
+                    writeln!(io::stderr().lock(), "{} {}", prefix, text)
+                } else {
+                    writeln!(io::stdout().lock(), "{} {}", prefix, text)
+                };
+
+                line.clear();
+            }
+        })
+    }
+
+    /// Asks every running program to stop with its stop_signal, or `fallback` when it has none.
+    fn stop_all(&mut self, fallback: libc::c_int) {
+        for program in self.programs.iter_mut().filter(|program| program.status.is_none()) {
+            if program.deadline.is_some() {
+                continue;
+            }
+
+            signal_group(program.child.id() as i32, program.policy.signal.unwrap_or(fallback));
+            program.deadline = Some(Instant::now() + program.policy.timeout);
+        }
+    }
+
+    /// SIGKILLs programs whose grace period ran out, or all of them when `now` is set.
+    fn kill_overdue(&mut self, now: bool) {
+        for program in self.programs.iter_mut().filter(|program| program.status.is_none() && !program.killed) {
+            let Some(deadline) = program.deadline else { continue };
+
+            if now || Instant::now() >= deadline {
+                signal_group(program.child.id() as i32, libc::SIGKILL);
+                eprintln!("Force killed {} (pid {}).", program.name, program.child.id());
+                program.killed = true;
+            }
+        }
+    }
+
+    /// Waits for every program to exit, forwarding SIGINT/SIGTERM to their process groups.
+    /// Returns each program's exit code in exit order and the code of the first failure.
+    pub fn wait(mut self) -> Result<(Vec<(String, i32)>, i32)> {
+        let mut exits = Vec::new();
+        let mut failure: Option<i32> = None;
+        let mut stopping = false;
+
+        while self.programs.iter().any(|program| program.status.is_none()) {
+            match SIGNAL.swap(0, Ordering::SeqCst) {
+                0 => (),
+                // A second interrupt while stopping means stop now
+                _ if stopping => self.kill_overdue(true),
+                signal => {
+                    stopping = true;
+                    self.stop_all(signal);
+                }
+            }
+
+            self.kill_overdue(false);
+
+            for index in 0..self.programs.len() {
+                let program = &mut self.programs[index];
+                if program.status.is_some() {
+                    continue;
+                }
+
+                let Some(status) = program.child.try_wait().context("Failed to wait on program.")? else { continue };
+                program.status = Some(status);
+
+                let code = exit_code(status);
+                exits.push((program.name.to_string(), code));
+
+                if code != 0 && failure.is_none() {
+                    failure = Some(code);
+
+                    if !stopping {
+                        eprintln!("{} exited with code {}, stopping the others.", program.name, code);
+                        stopping = true;
+                        self.stop_all(libc::SIGTERM);
+                    }
+                }
+            }
+
+            thread::sleep(POLL);
+        }
+
+        for program in self.programs {
+            for reader in program.readers {
+                reader.join().map_err(|_| anyhow!("Output of {} could not be read.", program.name))?;
+            }
+        }
+
+        for (name, command) in &self.teardowns {
+            let status = Command::new("sh")
+                .args(["-c", command])
+                .status()
+                .context("Failed to run teardown command.")?;
+
+            if !status.success() {
+                eprintln!("Teardown of {} failed: {}", name, status);
+            }
+        }
+
+        Ok((exits, failure.unwrap_or(0)))
+    }
+
+    /// Stops everything already started, used when a launch fails part way.
+    pub fn abort(mut self) -> Result<()> {
+        self.stop_all(libc::SIGTERM);
+        self.wait().map(|_| ())
+    }
+}
+
+/// The exit code of a finished program, 128 plus the signal when it was killed like in a shell.
+pub(crate) fn exit_code(status: ExitStatus) -> i32 {
+    status.code()
+        .or_else(|| status.signal().map(|signal| 128 + signal))
+        .unwrap_or(1)
+}
+
+/// Waits for a child that shares the terminal and returns its exit code. Ctrl-C and Ctrl-\ are
+/// the child's to handle, so devinit ignores them from here on.
+pub(crate) fn wait_foreground(child: &mut Child) -> Result<i32> {
+    // SAFETY: ignoring signals has no memory safety requirements.
+    unsafe {
+        libc::signal(libc::SIGINT, libc::SIG_IGN);
+        libc::signal(libc::SIGQUIT, libc::SIG_IGN);
+    }
+
+    let status = child.wait().context("Failed to wait on child process.")?;
+    Ok(exit_code(status))
+}
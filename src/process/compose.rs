@@ -0,0 +1,290 @@
+use anyhow::{anyhow, Result, Context};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::{expand_home, Program};
+use crate::process::ProcessManager;
+
+/// How long to wait for compose services to report ready.
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(120);
+const HEALTH_POLL: Duration = Duration::from_millis(500);
+
+/// A `kind = "compose"` program, driven through the `docker compose` CLI.
+pub struct Compose<'a> {
+    /// The docker binary, looked up in PATH unless it is a path.
+    docker: PathBuf,
+    name: &'a str,
+    working_directory: Option<String>,
+    compose_file: Option<&'a str>,
+    project_name: Option<&'a str>,
+    services: &'a [String],
+}
+
+impl<'a> Compose<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Compose {
+            docker: PathBuf::from("docker"),
+            name: &program.name,
+            working_directory: program.working_directory.as_deref().map(expand_home),
+            compose_file: program.compose_file.as_deref(),
+            project_name: program.project_name.as_deref(),
+            services: program.services.as_deref().unwrap_or_default(),
+        }
+    }
+
+    /// Drives another docker binary, like a stub in tests.
+    #[cfg(test)]
+    fn with_docker(self, docker: PathBuf) -> Self {
+        Compose { docker, ..self }
+    }
+
+    /// Arguments shared by every `docker compose` invocation for this program.
+    fn base_args(&self) -> Vec<String> {
+        let mut args = vec!["compose".to_string()];
+
+        if let Some(file) = self.compose_file {
+            args.extend(["-f".to_string(), file.to_string()]);
+        }
+
+        if let Some(project_name) = self.project_name {
+            args.extend(["-p".to_string(), project_name.to_string()]);
+        }
+
+        args
+    }
+
+    fn docker(&self, args: &[&str]) -> Command {
+        let mut cmd = Command::new(&self.docker);
+        cmd.args(self.base_args()).args(args);
+
+        if let Some(dir) = &self.working_directory {
+            cmd.current_dir(dir);
+        }
+
+        cmd
+    }
+
+    /// Brings the services up detached and blocks until each container is ready.
+    pub fn up(&self) -> Result<()> {
+        let mut args = vec!["up", "-d"];
+        args.extend(self.services.iter().map(String::as_str));
+
+        let status = self.docker(&args)
+            .stdin(Stdio::null())
+            .status()
+            .context("Failed to execute docker compose:")?;
+
+        if !status.success() {
+            return Err(anyhow!("docker compose up for {} failed: {}", self.name, status));
+        }
+
+        self.wait_healthy()
+    }
+
+    fn container_ids(&self) -> Result<Vec<String>> {
+        let mut args = vec!["ps", "-q"];
+        args.extend(self.services.iter().map(String::as_str));
+
+        let output = self.docker(&args)
+            .output()
+            .context("Failed to list compose containers.")?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "docker compose ps for {} failed: {}",
+                self.name,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(String::from_utf8(output.stdout)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect())
+    }
+
+    /// Polls `docker inspect` until every container is healthy, or running when it has no healthcheck.
+    /// One-shot containers, like migrations or other init jobs, are done once they exited with 0.
+    fn wait_healthy(&self) -> Result<()> {
+        let ids = self.container_ids()?;
+
+        if ids.is_empty() {
+            return Err(anyhow!("docker compose started no containers for {}.", self.name));
+        }
+
+        let deadline = Instant::now() + HEALTH_TIMEOUT;
+
+        println!("Waiting for {} to become healthy...", self.name);
+
+        loop {
+            let output = Command::new(&self.docker)
+                .args([
+                    "inspect",
+                    "--format",
+                    "{{.Name}} {{if .State.Health}}{{.State.Health.Status}}{{else}}{{.State.Status}}{{end}} {{.State.ExitCode}}",
+                ])
+                .args(&ids)
+                .output()
+                .context("Failed to inspect compose containers.")?;
+
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "docker inspect for {} failed: {}",
+                    self.name,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+
+            let statuses = String::from_utf8(output.stdout)?;
+            let mut pending = Vec::new();
+
+            for line in statuses.lines().filter(|line| !line.trim().is_empty()) {
+                let mut fields = line.trim().rsplitn(3, ' ');
+                let code = fields.next().unwrap_or_default();
+                let status = fields.next().unwrap_or_default();
+                let container = fields.next().unwrap_or_default().trim_start_matches('/');
+
+                match (status, code) {
+                    ("healthy" | "running", _) | ("exited", "0") => (),
+                    ("exited", code) => {
+                        return Err(anyhow!("Container {} of {} exited with {}.", container, self.name, code));
+                    }
+                    ("unhealthy" | "dead", _) => {
+                        return Err(anyhow!("Container {} of {} is {}.", container, self.name, status));
+                    }
+                    _ => pending.push(container.to_string()),
+                }
+            }
+
+            if pending.is_empty() {
+                println!("{} is healthy.", self.name);
+                return Ok(());
+            }
+
+            if Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "Timed out waiting for {} to become healthy: {}",
+                    self.name,
+                    pending.join(", ")
+                ));
+            }
+
+            thread::sleep(HEALTH_POLL);
+        }
+    }
+
+    /// Shell command that tears down exactly what `up` started.
+    pub fn down_command(&self) -> String {
+        let mut args: Vec<String> = vec![self.docker.to_string_lossy().into_owned()];
+        args.extend(self.base_args());
+
+        if self.services.is_empty() {
+            args.push("down".to_string());
+        } else {
+            args.extend(["rm".to_string(), "--stop".to_string(), "--force".to_string()]);
+            args.extend(self.services.iter().cloned());
+        }
+
+        let command = args
+            .iter()
+            .map(|arg| ProcessManager::shell_quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        match &self.working_directory {
+            Some(dir) => format!("cd {} && {}", ProcessManager::shell_quote(dir), command),
+            None => command,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+
+    /// Logs every call and answers like docker would, reporting the containers as starting on
+    /// the first inspect and with the contents of the `inspect` file after that.
+    const STUB: &str = r#"#!/bin/sh
+dir="$(dirname "$0")"
+echo "$*" >> "$dir/calls"
+
+case "$*" in
+    *" ps -q"*) printf 'c1\nc2\n' ;;
+    inspect*)
+        if [ -f "$dir/inspected" ]; then
+            cat "$dir/inspect"
+        else
+            touch "$dir/inspected"
+            printf '/demo-web-1 starting 0\n/demo-init-1 running 0\n'
+        fi
+        ;;
+esac
+"#;
+
+    fn stub(dir: &Path, inspect: &str) {
+        for file in ["calls", "inspected"] {
+            let _ = fs::remove_file(dir.join(file));
+        }
+
+        fs::write(dir.join("inspect"), inspect).unwrap();
+    }
+
+    fn calls(dir: &Path) -> Vec<String> {
+        fs::read_to_string(dir.join("calls"))
+            .unwrap_or_default()
+            .lines()
+            // The inspect format is long and beside the point, keep the container ids
+            .map(|line| match line.rsplit_once("}} ") {
+                Some((_, ids)) => format!("inspect {}", ids),
+                None => line.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn drives_docker_compose_through_up_health_and_down() {
+        let dir = std::env::temp_dir().join(format!("devinit-docker-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let docker = dir.join("docker");
+        fs::write(&docker, STUB).unwrap();
+        fs::set_permissions(&docker, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let program = Program {
+            name: "db".to_string(),
+            kind: Some("compose".to_string()),
+            compose_file: Some("compose.yml".to_string()),
+            project_name: Some("demo".to_string()),
+            ..Default::default()
+        };
+        let compose = Compose::new(&program).with_docker(docker);
+
+        // A one-shot init container that exited cleanly counts as done
+        stub(&dir, "/demo-web-1 healthy 0\n/demo-init-1 exited 0\n");
+        compose.up().unwrap();
+
+        assert_eq!(calls(&dir), [
+            "compose -f compose.yml -p demo up -d",
+            "compose -f compose.yml -p demo ps -q",
+            "inspect c1 c2",
+            "inspect c1 c2",
+        ]);
+
+        let status = Command::new("sh").arg("-c").arg(compose.down_command()).status().unwrap();
+        assert!(status.success());
+        assert_eq!(calls(&dir).last().unwrap(), "compose -f compose.yml -p demo down");
+
+        stub(&dir, "/demo-web-1 healthy 0\n/demo-init-1 exited 3\n");
+        let error = compose.up().unwrap_err().to_string();
+        assert!(error.contains("demo-init-1 of db exited with 3"), "{}", error);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,247 @@
+use anyhow::{anyhow, Result, Context};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::config::{expand_home, Program};
+
+impl Program {
+    /// The variables the program runs with on top of devinit's own environment: its env_file,
+    /// then its env table. `${VAR}` and `${VAR:-default}` in values are interpolated from the
+    /// env_file and the environment devinit was started with.
+    pub fn environment(&self) -> Result<Vec<(String, String)>> {
+        let mut vars = match &self.env_file {
+            Some(file) => self.read_env_file(file)?,
+            None => Vec::new(),
+        };
+
+        if let Some(env) = &self.env {
+            let file: HashMap<String, String> = vars.iter().cloned().collect();
+
+            // Sorted so a key set in both the file and the table always ends up with the table's value
+            let mut entries: Vec<(&String, &String)> = env.iter().collect();
+            entries.sort();
+
+            for (key, value) in entries {
+                vars.push((key.to_string(), interpolate(value, |name| file.get(name).cloned())));
+            }
+        }
+
+        Ok(vars)
+    }
+
+    /// Reads a dotenv file, relative to the working directory unless absolute.
+    fn read_env_file(&self, file: &str) -> Result<Vec<(String, String)>> {
+        let file = expand_home(file);
+        let path = match &self.working_directory {
+            Some(dir) if !Path::new(&file).is_absolute() => Path::new(&expand_home(dir)).join(&file),
+            _ => Path::new(&file).to_path_buf(),
+        };
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read env_file {} of {}.", path.display(), self.name))?;
+
+        let mut vars: Vec<(String, String)> = Vec::new();
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let line = line.strip_prefix("export ").unwrap_or(line);
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Line {} of {} is not of the form KEY=VALUE.", number + 1, path.display()))?;
+
+            let lookup = |name: &str| {
+                vars.iter()
+                    .rev()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.to_string())
+            };
+
+            let value = value.trim();
+            let value = if let Some(literal) = quoted(value, '\'') {
+                literal.to_string()
+            } else if let Some(inner) = quoted(value, '"') {
+                interpolate(&unescape(inner), lookup)
+            } else {
+                // Unquoted values end at a comment
+                let value = value.split(" #").next().unwrap_or(value).trim_end();
+                interpolate(value, lookup)
+            };
+
+            vars.push((key.trim().to_string(), value));
+        }
+
+        Ok(vars)
+    }
+}
+
+/// The inside of a value wrapped in `quote`, which may be followed by a comment. Inside double
+/// quotes a backslash escapes the quote.
+fn quoted(value: &str, quote: char) -> Option<&str> {
+    let inner = value.strip_prefix(quote)?;
+    let mut escaped = false;
+
+    for (index, c) in inner.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' && quote == '"' {
+            escaped = true;
+        } else if c == quote {
+            let rest = inner[index + 1..].trim_start();
+            return (rest.is_empty() || rest.starts_with('#')).then_some(&inner[..index]);
+        }
+    }
+
+    None
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+/// Replaces `${VAR}` and `${VAR:-default}`, looking variables up in `lookup` and then the
+/// environment. Unset variables without a default become empty, like in a shell.
+fn interpolate(value: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else { break };
+
+        result.push_str(&rest[..start]);
+
+        let expression = &rest[start + 2..start + end];
+        let (name, default) = match expression.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expression, None),
+        };
+
+        let value = lookup(name)
+            .or_else(|| std::env::var(name).ok())
+            .filter(|value| !value.is_empty() || default.is_none());
+
+        result.push_str(value.as_deref().or(default).unwrap_or(""));
+        rest = &rest[start + end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn environment(env_file: &str, env: &[(&str, &str)]) -> Vec<(String, String)> {
+        let dir = std::env::temp_dir().join(format!("devinit-env-{}-{}", std::process::id(), env.len()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(".env"), env_file).unwrap();
+
+        let program = Program {
+            name: "test".to_string(),
+            working_directory: Some(dir.to_string_lossy().to_string()),
+            env_file: Some(".env".to_string()),
+            env: (!env.is_empty()).then(|| env.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()),
+            ..Default::default()
+        };
+
+        let vars = program.environment();
+        fs::remove_dir_all(&dir).unwrap();
+        vars.unwrap()
+    }
+
+    fn get<'a>(vars: &'a [(String, String)], key: &str) -> Option<&'a str> {
+        vars.iter().rev().find(|(name, _)| name == key).map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn reads_dotenv_files() {
+        let vars = environment(
+            concat!(
+                "# a comment\n",
+                "\n",
+                "PLAIN=value # comment\n",
+                "export EXPORTED=yes\n",
+                "SINGLE='${PLAIN} stays' # comment\n",
+                "DOUBLE=\"line\\n${PLAIN}\" # comment\n",
+                "ESCAPED=\"say \\\"hi\\\"\"\n",
+                "HASH=\"a # b\"\n",
+                "  SPACED  =  padded  \n",
+            ),
+            &[],
+        );
+
+        assert_eq!(get(&vars, "PLAIN"), Some("value"));
+        assert_eq!(get(&vars, "EXPORTED"), Some("yes"));
+        assert_eq!(get(&vars, "SINGLE"), Some("${PLAIN} stays"));
+        assert_eq!(get(&vars, "DOUBLE"), Some("line\nvalue"));
+        assert_eq!(get(&vars, "ESCAPED"), Some("say \"hi\""));
+        assert_eq!(get(&vars, "HASH"), Some("a # b"));
+        assert_eq!(get(&vars, "SPACED"), Some("padded"));
+    }
+
+    #[test]
+    fn rejects_lines_without_a_value() {
+        let dir = std::env::temp_dir().join(format!("devinit-env-invalid-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(".env"), "KEY=value\nnonsense\n").unwrap();
+
+        let program = Program {
+            name: "test".to_string(),
+            working_directory: Some(dir.to_string_lossy().to_string()),
+            env_file: Some(".env".to_string()),
+            ..Default::default()
+        };
+
+        let error = program.environment().unwrap_err().to_string();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(error.starts_with("Line 2 of"), "{}", error);
+    }
+
+    #[test]
+    fn the_env_table_wins_over_the_file() {
+        let vars = environment("SHARED=file\nBASE=base\n", &[("SHARED", "table ${BASE}"), ("ONLY", "${SHARED}")]);
+
+        assert_eq!(get(&vars, "SHARED"), Some("table base"));
+        assert_eq!(get(&vars, "BASE"), Some("base"));
+        // The table interpolates from the file, not from itself
+        assert_eq!(get(&vars, "ONLY"), Some("file"));
+    }
+
+    #[test]
+    fn interpolates_with_defaults() {
+        let lookup = |name: &str| match name {
+            "SET" => Some("set".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+
+        assert_eq!(interpolate("${SET}/${SET:-other}", lookup), "set/set");
+        assert_eq!(interpolate("${EMPTY:-fallback}|${EMPTY}", lookup), "fallback|");
+        assert_eq!(interpolate("${DEVINIT_TEST_UNSET:-fallback}|${DEVINIT_TEST_UNSET}", lookup), "fallback|");
+        assert_eq!(interpolate("no ${closing", lookup), "no ${closing");
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::{anyhow, Result, Context};
+use std::process::Command;
+
+use crate::config::Hooks;
+
+/// Points in a launch where hooks run.
+#[derive(Debug, Clone, Copy)]
+pub enum HookEvent {
+    PreLaunch,
+    PostLaunch,
+    OnFailure,
+    OnExit,
+}
+
+impl HookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::PreLaunch => "pre_launch",
+            HookEvent::PostLaunch => "post_launch",
+            HookEvent::OnFailure => "on_failure",
+            HookEvent::OnExit => "on_exit",
+        }
+    }
+}
+
+/// What a hook is told about the launch through DEVINIT_* variables.
+pub struct HookContext<'a> {
+    pub project: &'a str,
+    pub program: Option<&'a str>,
+    pub exit_code: Option<i32>,
+}
+
+impl<'a> HookContext<'a> {
+    pub fn project(project: &'a str) -> Self {
+        HookContext { project, program: None, exit_code: None }
+    }
+
+    pub fn program(&self, program: &'a str) -> Self {
+        HookContext { project: self.project, program: Some(program), exit_code: self.exit_code }
+    }
+
+    pub fn exited(&self, code: i32) -> Self {
+        HookContext { project: self.project, program: self.program, exit_code: Some(code) }
+    }
+}
+
+impl Hooks {
+    fn command(&self, event: HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::PreLaunch => self.pre_launch.as_deref(),
+            HookEvent::PostLaunch => self.post_launch.as_deref(),
+            HookEvent::OnFailure => self.on_failure.as_deref(),
+            HookEvent::OnExit => self.on_exit.as_deref(),
+        }
+    }
+
+    /// Runs the hook for an event through `sh -c`.
+    /// A failing pre_launch hook aborts the launch, other failures are only reported.
+    pub fn run(&self, event: HookEvent, context: &HookContext) -> Result<()> {
+        let Some(command) = self.command(event) else { return Ok(()) };
+
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command])
+            .env("DEVINIT_HOOK", event.name())
+            .env("DEVINIT_PROJECT", context.project);
+
+        if let Some(program) = context.program {
+            cmd.env("DEVINIT_PROGRAM", program);
+        }
+
+        if let Some(code) = context.exit_code {
+            cmd.env("DEVINIT_EXIT_CODE", code.to_string());
+        }
+
+        let status = cmd
+            .status()
+            .with_context(|| format!("Failed to run {} hook.", event.name()))?;
+
+        match (status.success(), event) {
+            (true, _) => Ok(()),
+            (false, HookEvent::PreLaunch) => Err(anyhow!("pre_launch hook failed: {}", status)),
+            (false, _) => {
+                eprintln!("Warning: {} hook failed: {}", event.name(), status);
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::{anyhow, Result};
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+
+use crate::config::Limits;
+
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+/// Limits resolved into the raw values the child applies between fork and exec.
+#[derive(Clone, Copy)]
+struct Resolved {
+    nice: Option<libc::c_int>,
+    ioprio: Option<libc::c_int>,
+    nofile: Option<libc::rlim_t>,
+    address_space: Option<libc::rlim_t>,
+    cpu_time: Option<libc::rlim_t>,
+    affinity: Option<libc::cpu_set_t>,
+}
+
+impl Limits {
+    /// Validates the limits and installs a pre_exec hook applying them to the command.
+    pub fn apply(&self, cmd: &mut Command) -> Result<()> {
+        let resolved = self.resolve()?;
+
+        // SAFETY: the hook only makes raw syscalls on precomputed values, which is async-signal-safe.
+        unsafe {
+            cmd.pre_exec(move || resolved.apply());
+        }
+
+        Ok(())
+    }
+
+    fn resolve(&self) -> Result<Resolved> {
+        if let Some(nice) = self.nice
+            && !(-20..=19).contains(&nice) {
+            return Err(anyhow!("nice must be between -20 and 19, got {}.", nice));
+        }
+
+        let level = self.ionice_level.unwrap_or(4);
+        if level > 7 {
+            return Err(anyhow!("ionice_level must be between 0 and 7, got {}.", level));
+        }
+
+        let ioprio = match self.ionice.as_deref() {
+            None => None,
+            Some("realtime") => Some((1 << IOPRIO_CLASS_SHIFT) | level as libc::c_int),
+            Some("best-effort") => Some((2 << IOPRIO_CLASS_SHIFT) | level as libc::c_int),
+            Some("idle") => Some(3 << IOPRIO_CLASS_SHIFT),
+            Some(other) => return Err(anyhow!("Unknown ionice class {}, expected realtime, best-effort or idle.", other)),
+        };
+
+        let affinity = match &self.cpu_affinity {
+            None => None,
+            Some(cpus) => {
+                // SAFETY: cpu_set_t is plain data and CPU_ZERO/CPU_SET only touch the set passed in.
+                let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+                unsafe { libc::CPU_ZERO(&mut set) };
+
+                for cpu in cpus {
+                    if *cpu >= libc::CPU_SETSIZE as usize {
+                        return Err(anyhow!("CPU {} is out of range for cpu_affinity.", cpu));
+                    }
+                    unsafe { libc::CPU_SET(*cpu, &mut set) };
+                }
+
+                Some(set)
+            }
+        };
+
+        Ok(Resolved {
+            nice: self.nice,
+            ioprio,
+            nofile: self.nofile,
+            address_space: self.address_space,
+            cpu_time: self.cpu_time,
+            affinity,
+        })
+    }
+}
+
+impl Resolved {
+    fn apply(&self) -> io::Result<()> {
+        // SAFETY: plain syscalls on values owned by this function.
+        unsafe {
+            if let Some(nice) = self.nice
+                && libc::setpriority(libc::PRIO_PROCESS, 0, nice) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            if let Some(ioprio) = self.ioprio
+                && libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            for (resource, value) in [
+                (libc::RLIMIT_NOFILE, self.nofile),
+                (libc::RLIMIT_AS, self.address_space),
+                (libc::RLIMIT_CPU, self.cpu_time),
+            ] {
+                let Some(value) = value else { continue };
+
+                let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+                if libc::getrlimit(resource, &mut limit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+
+                // Only raise the hard limit when the soft limit needs it, which requires privileges
+                limit.rlim_cur = value;
+                limit.rlim_max = limit.rlim_max.max(value);
+
+                if libc::setrlimit(resource, &limit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            if let Some(set) = &self.affinity
+                && libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), set) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(limits: Limits) -> String {
+        match limits.resolve() {
+            Ok(_) => panic!("the limits were accepted"),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn rejects_nice_out_of_range() {
+        assert_eq!(error(Limits { nice: Some(20), ..Default::default() }), "nice must be between -20 and 19, got 20.");
+        assert_eq!(error(Limits { nice: Some(-21), ..Default::default() }), "nice must be between -20 and 19, got -21.");
+        assert_eq!(Limits { nice: Some(-20), ..Default::default() }.resolve().unwrap().nice, Some(-20));
+    }
+
+    #[test]
+    fn rejects_unknown_ionice_classes() {
+        let limits = Limits { ionice: Some("fast".to_string()), ..Default::default() };
+
+        assert_eq!(error(limits), "Unknown ionice class fast, expected realtime, best-effort or idle.");
+        assert_eq!(
+            error(Limits { ionice: Some("idle".to_string()), ionice_level: Some(8), ..Default::default() }),
+            "ionice_level must be between 0 and 7, got 8."
+        );
+    }
+
+    #[test]
+    fn encodes_the_ionice_class_above_the_level() {
+        let ioprio = |class: &str, level: Option<u8>| {
+            Limits { ionice: Some(class.to_string()), ionice_level: level, ..Default::default() }
+                .resolve()
+                .unwrap()
+                .ioprio
+        };
+
+        assert_eq!(ioprio("realtime", Some(2)), Some((1 << 13) | 2));
+        assert_eq!(ioprio("best-effort", Some(7)), Some((2 << 13) | 7));
+        // The kernel's default level when none is given
+        assert_eq!(ioprio("best-effort", None), Some((2 << 13) | 4));
+        assert_eq!(ioprio("idle", Some(7)), Some(3 << 13));
+    }
+
+    #[test]
+    fn checks_cpu_affinity() {
+        let out_of_range = libc::CPU_SETSIZE as usize;
+        let limits = Limits { cpu_affinity: Some(vec![0, out_of_range]), ..Default::default() };
+
+        assert_eq!(error(limits), format!("CPU {} is out of range for cpu_affinity.", out_of_range));
+
+        let set = Limits { cpu_affinity: Some(vec![0, 3]), ..Default::default() }
+            .resolve()
+            .unwrap()
+            .affinity
+            .unwrap();
+
+        // SAFETY: CPU_ISSET only reads the set passed in.
+        let cpus: Vec<usize> = (0..8).filter(|cpu| unsafe { libc::CPU_ISSET(*cpu, &set) }).collect();
+        assert_eq!(cpus, [0, 3]);
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::{anyhow, Result, Context};
+use std::process::Stdio;
+use std::process::{Child, Command};
+use std::io::Write;
+use std::os::unix::process::CommandExt;
+
+use crate::config::{expand_home, Program};
+use crate::db::{running::Running, settings::Settings};
+use crate::process::stop::{StopPolicy, Stopped, same_process};
+
+pub(super) mod attach;
+pub(super) mod compose;
+pub(super) mod env;
+pub(super) mod hooks;
+pub(super) mod limits;
+pub(super) mod ports;
+pub(super) mod stop;
+
+pub struct ProcessManager;
+
+impl ProcessManager {
+    /// Spawns a program and returns the pid of the launched process.
+    pub fn run_program(program: &Program) -> Result<u32> {
+        let mut cmd = Self::command(program)?;
+
+        // Handle output mode, the output_mode setting applies when the program has none
+        let output_mode = match &program.output_mode {
+            Some(mode) => Some(mode.clone()),
+            None => Settings::get("output_mode")?,
+        };
+
+        match output_mode.as_deref() {
+            Some("null") => {
+                cmd.stdout(Stdio::null());
+                cmd.stderr(Stdio::null());
+            }
+            Some("inherit") => {
+                cmd.stdout(Stdio::inherit());
+                cmd.stderr(Stdio::inherit());
+            }
+            Some("log") => {
+                // TODO: Implement logging to a file
+                cmd.stdout(Stdio::piped());
+                cmd.stderr(Stdio::piped());
+            }
+            _ => {}
+        }
+
+        let child = Self::spawn(&mut cmd, program)?;
+
+        Ok(child.id())
+    }
+
+    /// Builds the command for a program, without deciding where its output goes.
+    pub fn command(program: &Program) -> Result<Command> {
+        if program.path.is_empty() {
+            return Err(anyhow!("Program {} has no path to execute.", program.name));
+        }
+
+        let mut cmd: Command =
+            if let Some(settings) = &program.settings {
+                if settings.uwsm {
+                    let mut temp = Command::new("uwsm");
+                    temp.args(["app", "--", &program.path]);
+                    temp
+                }
+                else {
+                    Command::new(&program.path)
+                }
+            }
+            else {
+                Command::new(&program.path)
+            };
+
+
+        // Give each program its own process group so stopping reaches its children too
+        cmd.process_group(0);
+
+        // Set working directory
+        if let Some(dir) = &program.working_directory {
+            cmd.current_dir(expand_home(dir));
+        }
+
+        // Add arguments
+        if let Some(args) = &program.args {
+            cmd.args(args);
+        }
+
+        // Add environment variables
+        cmd.envs(program.environment()?);
+
+        // Apply priority and resource limits in the child before it executes
+        if let Some(limits) = &program.limits {
+            limits.apply(&mut cmd)?;
+        }
+
+        Ok(cmd)
+    }
+
+    /// Spawns the command and feeds the program's commands to its stdin.
+    pub fn spawn(cmd: &mut Command, program: &Program) -> Result<Child> {
+        // Execute the command
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .spawn()
+            .context("Failed to execute program:")?;
+
+        // Write commands to stdin
+        if let Some(commands) = &program.commands
+            && let Some(mut stdin) = child.stdin.take() {
+            for command in commands {
+                stdin.write_all(format!("{}\n", command).as_bytes())?;
+                stdin.flush()?;
+            }
+        }
+
+        Ok(child)
+    }
+
+    /// Undoes a recorded launch, stopping the process group and running its teardown command.
+    /// Returns the code the program ended with, 128 plus the signal that ended it like a shell
+    /// reports it, or 0 when it was already gone.
+    pub fn stop(entry: &Running, policy: &StopPolicy) -> Result<i32> {
+        let mut code = 0;
+
+        if let Some(pid) = entry.pid {
+            // After a reboot or pid reuse the pid belongs to an unrelated process
+            let stopped = if same_process(pid, entry.started) { policy.stop_group(pid) } else { Stopped::NotRunning };
+
+            match stopped {
+                Stopped::Exited => {
+                    println!("Stopped {} (pid {}).", entry.name, pid);
+                    code = 128 + policy.signal();
+                }
+                Stopped::Killed => {
+                    println!(
+                        "Force killed {} (pid {}) after {:?} without exiting.",
+                        entry.name, pid, policy.timeout
+                    );
+                    code = 128 + libc::SIGKILL;
+                }
+                Stopped::NotRunning => println!("{} (pid {}) is no longer running.", entry.name, pid),
+            }
+        }
+
+        if let Some(teardown) = &entry.teardown {
+            let status = Command::new("sh")
+                .args(["-c", teardown])
+                .status()
+                .context("Failed to run teardown command.")?;
+
+            if !status.success() {
+                return Err(anyhow!("Teardown of {} failed: {}", entry.name, status));
+            }
+
+            println!("Tore down {}.", entry.name);
+        }
+
+        Ok(code)
+    }
+
+    /// Quotes an argument so it survives being passed through `sh -c`.
+    pub fn shell_quote(arg: &str) -> String {
+        if !arg.is_empty()
+            && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:@%+,".contains(c)) {
+            return arg.to_string();
+        }
+
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
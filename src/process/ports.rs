@@ -0,0 +1,205 @@
+use anyhow::{anyhow, Result};
+use dialoguer::Select;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::Program;
+use crate::db::running::Running;
+use crate::process::{ProcessManager, stop::StopPolicy};
+
+/// How long to wait for a stopped process to release its port.
+const RELEASE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A process found listening on a port a program wants.
+pub struct Listener {
+    pub port: u16,
+    pub pid: Option<i32>,
+}
+
+impl Listener {
+    /// Finds whoever is listening on `port`, if anyone.
+    pub fn find(port: u16) -> Option<Listener> {
+        let inodes = Self::socket_inodes(port);
+
+        // procfs can miss sockets from other namespaces, so a bind attempt has the final say.
+        // Other bind errors, like EACCES below port 1024, say nothing about the port being taken.
+        let in_use = |result: io::Result<TcpListener>| matches!(result, Err(error) if error.kind() == ErrorKind::AddrInUse);
+
+        if inodes.is_empty() && !in_use(TcpListener::bind(("0.0.0.0", port))) {
+            return None;
+        }
+
+        Some(Listener { port, pid: Self::owner_pid(&inodes) })
+    }
+
+    /// Inodes of listening sockets bound to `port` according to procfs.
+    fn socket_inodes(port: u16) -> Vec<String> {
+        let mut inodes = Vec::new();
+
+        for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+            let Ok(contents) = fs::read_to_string(table) else { continue };
+
+            for line in contents.lines().skip(1) {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+
+                // Only sockets in the LISTEN (0A) state
+                if fields.len() < 10 || fields[3] != "0A" {
+                    continue;
+                }
+
+                let local_port = fields[1]
+                    .rsplit_once(':')
+                    .and_then(|(_, hex)| u16::from_str_radix(hex, 16).ok());
+
+                if local_port == Some(port) {
+                    inodes.push(fields[9].to_string());
+                }
+            }
+        }
+
+        inodes
+    }
+
+    /// Scans /proc/<pid>/fd for a descriptor pointing at one of the sockets.
+    fn owner_pid(inodes: &[String]) -> Option<i32> {
+        if inodes.is_empty() {
+            return None;
+        }
+
+        let targets: Vec<String> = inodes
+            .iter()
+            .map(|inode| format!("socket:[{}]", inode))
+            .collect();
+
+        for entry in fs::read_dir("/proc").ok()?.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<i32>().ok()) else { continue };
+            let Ok(fds) = fs::read_dir(entry.path().join("fd")) else { continue };
+
+            for fd in fds.flatten() {
+                if let Ok(link) = fs::read_link(fd.path())
+                    && targets.iter().any(|target| link.as_os_str() == target.as_str()) {
+                    return Some(pid);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn describe(&self) -> String {
+        let Some(pid) = self.pid else {
+            return format!("Port {} is already in use by an unknown process", self.port);
+        };
+
+        let command = fs::read_to_string(format!("/proc/{}/comm", pid))
+            .map(|comm| comm.trim().to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
+
+        match Running::owner(pid).ok().flatten() {
+            Some((project, entry)) => format!(
+                "Port {} is held by {} (pid {}), launched as {} of project {}",
+                self.port, command, pid, entry.name, project.name
+            ),
+            None => format!("Port {} is held by {} (pid {})", self.port, command, pid),
+        }
+    }
+
+    /// Stops the listener, the way `devinit stop` would when devinit launched it.
+    fn stop(&self, pid: i32) -> Result<()> {
+        match Running::owner(pid)? {
+            Some((project, entry)) => {
+                let config = project.config().ok();
+                let program = config
+                    .as_ref()
+                    .and_then(|config| config.programs.list.iter().find(|program| program.name == entry.name));
+
+                ProcessManager::stop(&entry, &StopPolicy::new(program)?)?;
+                entry.remove()?;
+            }
+            None => {
+                // SAFETY: kill has no memory safety requirements.
+                if unsafe { libc::kill(pid, libc::SIGTERM) } != 0 {
+                    return Err(anyhow!("Failed to signal pid {}.", pid));
+                }
+            }
+        }
+
+        let deadline = Instant::now() + RELEASE_TIMEOUT;
+
+        while Listener::find(self.port).is_some() {
+            if Instant::now() >= deadline {
+                return Err(anyhow!("Pid {} did not release port {}.", pid, self.port));
+            }
+
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        println!("Stopped pid {}, port {} is free.", pid, self.port);
+
+        Ok(())
+    }
+}
+
+pub struct Ports;
+
+impl Ports {
+    /// Checks a program's declared ports before launch.
+    /// Returns false when the user chose to skip the program.
+    pub fn resolve_conflicts(program: &Program) -> Result<bool> {
+        let Some(ports) = &program.ports else { return Ok(true) };
+
+        for port in ports {
+            let Some(listener) = Listener::find(*port) else { continue };
+
+            eprintln!("{}.", listener.describe());
+
+            let mut options = Vec::new();
+            if listener.pid.is_some() {
+                options.push("Stop it and continue");
+            }
+            options.push("Skip this program");
+            options.push("Abort launch");
+
+            let choice = Select::new()
+                .with_prompt(format!("{} needs port {}", program.name, port))
+                .items(&options)
+                .default(0)
+                .interact()?;
+
+            match (options[choice], listener.pid) {
+                ("Stop it and continue", Some(pid)) => listener.stop(pid)?,
+                ("Skip this program", _) => return Ok(false),
+                _ => return Err(anyhow!("Launch aborted: port {} is in use.", port)),
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_process_listening_on_a_port() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let found = Listener::find(port).unwrap();
+
+        assert_eq!(found.port, port);
+        assert_eq!(found.pid, Some(std::process::id() as i32));
+    }
+
+    #[test]
+    fn a_free_port_has_no_listener() {
+        // Whatever port the system handed out is free again once the listener is dropped
+        let port = TcpListener::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port();
+
+        assert!(Listener::find(port).is_none());
+    }
+}
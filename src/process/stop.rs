@@ -0,0 +1,154 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::Program;
+use crate::db::settings::Settings;
+
+const POLL: Duration = Duration::from_millis(100);
+
+/// How a program wants to be shut down: a signal, then SIGKILL once the grace period runs out.
+#[derive(Debug, Clone, Copy)]
+pub struct StopPolicy {
+    /// The configured stop_signal, None when the program left it to the caller.
+    pub signal: Option<libc::c_int>,
+    pub timeout: Duration,
+}
+
+/// How a stopped process group went down.
+pub enum Stopped {
+    Exited,
+    Killed,
+    NotRunning,
+}
+
+impl StopPolicy {
+    /// The policy of a program, the stop_timeout setting fills in what it leaves out.
+    pub fn new(program: Option<&Program>) -> Result<Self> {
+        let timeout = match program.and_then(|program| program.stop_timeout.clone()) {
+            Some(timeout) => timeout,
+            None => Settings::get("stop_timeout")?.unwrap_or_else(|| "10s".to_string()),
+        };
+
+        Ok(StopPolicy {
+            signal: program
+                .and_then(|program| program.stop_signal.as_deref())
+                .map(parse_signal)
+                .transpose()?,
+            timeout: parse_duration(&timeout)?,
+        })
+    }
+
+    /// The signal to send first, falling back to SIGTERM.
+    pub fn signal(&self) -> libc::c_int {
+        self.signal.unwrap_or(libc::SIGTERM)
+    }
+
+    /// Signals the process group led by `pid`, waits out the grace period, then escalates to SIGKILL.
+    pub fn stop_group(&self, pid: i32) -> Stopped {
+        if !signal_group(pid, self.signal()) {
+            return Stopped::NotRunning;
+        }
+
+        let deadline = Instant::now() + self.timeout;
+
+        while signal_group(pid, 0) {
+            if Instant::now() >= deadline {
+                signal_group(pid, libc::SIGKILL);
+                return Stopped::Killed;
+            }
+
+            thread::sleep(POLL);
+        }
+
+        Stopped::Exited
+    }
+}
+
+/// When the process `pid` started, in clock ticks since boot, from /proc/<pid>/stat.
+pub fn start_time(pid: i32) -> Option<i64> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+
+    // The command name can hold spaces and parentheses, the fields after it cannot.
+    // starttime is the 22nd field, the 20th after the name.
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+
+    fields.get(19)?.parse().ok()
+}
+
+/// Whether `pid` is still the process devinit launched at `started`, and not one that got its
+/// pid after a reboot or reuse. Without a recorded start time it has to still lead its own
+/// process group, as every launched program does.
+pub fn same_process(pid: i32, started: Option<i64>) -> bool {
+    match started {
+        Some(started) => start_time(pid) == Some(started),
+        // SAFETY: getpgid has no memory safety requirements.
+        None => unsafe { libc::getpgid(pid) == pid },
+    }
+}
+
+/// Sends a signal to the process group led by `pid`, or to the process alone when it leads no group.
+/// Returns false when nothing received it.
+pub fn signal_group(pid: i32, signal: libc::c_int) -> bool {
+    // SAFETY: kill has no memory safety requirements; a negative pid targets the process group.
+    unsafe { libc::kill(-pid, signal) == 0 || libc::kill(pid, signal) == 0 }
+}
+
+/// Parses a signal name like "SIGINT", "INT" or a plain number.
+pub fn parse_signal(name: &str) -> Result<libc::c_int> {
+    if let Ok(number) = name.parse::<libc::c_int>() {
+        return Ok(number);
+    }
+
+    let upper = name.to_ascii_uppercase();
+
+    match upper.strip_prefix("SIG").unwrap_or(&upper) {
+        "HUP" => Ok(libc::SIGHUP),
+        "INT" => Ok(libc::SIGINT),
+        "QUIT" => Ok(libc::SIGQUIT),
+        "KILL" => Ok(libc::SIGKILL),
+        "USR1" => Ok(libc::SIGUSR1),
+        "USR2" => Ok(libc::SIGUSR2),
+        "TERM" => Ok(libc::SIGTERM),
+        "STOP" => Ok(libc::SIGSTOP),
+        "WINCH" => Ok(libc::SIGWINCH),
+        _ => Err(anyhow!("Unknown signal: {}.", name)),
+    }
+}
+
+/// Parses a duration like "10s", "500ms", "2m" or "1h"; bare numbers are seconds.
+pub fn parse_duration(text: &str) -> Result<Duration> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+
+    let number: f64 = number
+        .parse()
+        .map_err(|_| anyhow!("Invalid duration: {}.", text))?;
+
+    let seconds = match unit.trim() {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return Err(anyhow!("Invalid duration unit in {}, expected ms, s, m or h.", text)),
+    };
+
+    Duration::try_from_secs_f64(seconds).map_err(|_| anyhow!("Invalid duration: {}.", text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_the_process_by_its_start_time() {
+        let pid = std::process::id() as i32;
+        let started = start_time(pid).unwrap();
+
+        assert!(same_process(pid, Some(started)));
+        assert!(!same_process(pid, Some(started + 1)));
+        assert!(!same_process(i32::MAX, Some(started)));
+    }
+}
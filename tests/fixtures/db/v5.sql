@@ -0,0 +1,48 @@
+-- A devinit database at schema version 5.
+CREATE TABLE alias (
+                id INTEGER NOT NULL,          -- Foreign key referencing the 'projects' table
+                alias TEXT NOT NULL,                  -- Alias name (string)
+                FOREIGN KEY (id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+INSERT INTO "alias" VALUES(1,'w');
+INSERT INTO "alias" VALUES(1,'site');
+CREATE TABLE launches (
+                    id INTEGER NOT NULL,          -- Foreign key referencing the 'projects' table
+                    alias TEXT NOT NULL,          -- Name or alias the project was launched with
+                    timestamp INTEGER NOT NULL,   -- Unix time of the launch
+                    duration INTEGER NOT NULL,    -- Milliseconds the launch took
+                    success BOOLEAN NOT NULL CHECK (success IN (0, 1)),
+                    FOREIGN KEY (id) REFERENCES projects(id) ON DELETE CASCADE
+                );
+INSERT INTO "launches" VALUES(1,'w',1700000000,120,1);
+CREATE TABLE projects (
+                id INTEGER PRIMARY KEY AUTOINCREMENT, -- Auto-incrementing unique ID
+                name TEXT NOT NULL,                   -- Name of the project (string)
+                toml TEXT NOT NULL                    -- TOML content (string)
+            );
+INSERT INTO "projects" VALUES(1,'web','root = "/srv/web"
+
+[[programs.list]]
+name = "server"
+path = "cargo"
+args = ["run"]
+');
+CREATE TABLE running (
+                    id INTEGER NOT NULL,          -- Foreign key referencing the 'projects' table
+                    name TEXT NOT NULL,           -- Name of the launched program
+                    pid INTEGER,                  -- Process id, if a process was spawned
+                    teardown TEXT,                -- Shell command undoing the launch
+                    FOREIGN KEY (id) REFERENCES projects(id) ON DELETE CASCADE
+                );
+INSERT INTO "running" VALUES(1,'server',4242,NULL);
+CREATE TABLE settings (
+                    uwsm BOOLEAN NOT NULL DEFAULT 0 CHECK (uwsm IN (0, 1))
+                , pre_launch TEXT, post_launch TEXT, on_failure TEXT, on_exit TEXT);
+INSERT INTO "settings" VALUES(1,'echo pre',NULL,'notify-send failed',NULL);
+CREATE TABLE templates (
+                    name TEXT PRIMARY KEY,        -- Name the template is used by
+                    toml TEXT NOT NULL            -- TOML content with {{placeholders}}
+                );
+INSERT INTO "templates" VALUES('rust','root = "{{root}}"
+');
+PRAGMA user_version = 5;